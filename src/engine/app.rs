@@ -1,8 +1,8 @@
 use std::{sync::{Arc}};
-use glam::{vec3};
+use glam::{vec3, Vec3};
 use winit::{application::ApplicationHandler, event::{DeviceEvent, DeviceId, WindowEvent}, event_loop::ActiveEventLoop, keyboard::{KeyCode, PhysicalKey}, window::{Window, WindowId}};
 
-use crate::engine::{components::{command_bus::command_bus::{CommandBus, CommandType}, entities::{entity::EntityType, entity_manager::EntityManager}, gamestage::gamestage::GameStage, input_manager::input_manager::InputManager, vulkan_manager::vulkan_manager::VulkanManager}, utils::{logger::{LogLevel, Logger}, structs::transform::Transform}, vulkan::{structs::{vertex::Vertex, viewport::ViewportInfo}, vulkan_container::VulkanContainer}};
+use crate::engine::{components::{command_bus::command_bus::{CommandBus, CommandType}, entities::{entity::EntityType, entity_manager::EntityManager}, gamestage::gamestage::GameStage, input_manager::input_manager::InputManager, ui_manager::ui_manager::UiManager, vulkan_manager::vulkan_manager::VulkanManager}, utils::{logger::{LogLevel, Logger}, structs::{light::{DirectionalLight, PointLight}, material::{Material, MaterialHandle}, skybox::SkyboxSource, transform::Transform}}, vulkan::{structs::{render_settings::RenderSettings, vertex::Vertex, viewport::ViewportInfo}, vulkan_container::VulkanContainer}};
 
 #[derive(Default)]
 pub struct App {
@@ -12,49 +12,70 @@ pub struct App {
     pub command_bus: Option<CommandBus>,
 }
 
-impl ApplicationHandler for App {
-    //[TO-DO]: This needs to be cleaned up and have dev stuff removed from it.
-    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
-        Logger::log(LogLevel::Medium, "app", "Resumed application...");
-
-        let window_attributes = Window::default_attributes();
-        self.window = Some(event_loop.create_window(window_attributes).unwrap().into());
-        self.window.as_ref().unwrap().request_redraw();
-        self.viewport_info = Some(ViewportInfo::new(
-            [0.0, 0.0],
-            [self.window.as_ref().unwrap().inner_size().width as f32, self.window.as_ref().unwrap().inner_size().height as f32]
-        ));
-
-        let vulkan_container = VulkanContainer::new(event_loop, self.window.clone().unwrap(), self.viewport_info.as_ref().unwrap());
-
-
+impl App {
+    //Without a window the UI still draws, but never gets any input.
+    pub fn create_command_bus(vulkan_container: VulkanContainer, window: Option<Arc<Window>>) -> CommandBus {
         let vulkan_manager = VulkanManager::new(vulkan_container);
         let entity_manager = EntityManager::new();
         
         //[TO-DO]: Should be made into an ini, yaml or json file for settings or something.
         let keys = vec![PhysicalKey::Code(KeyCode::KeyW), PhysicalKey::Code(KeyCode::KeyA), PhysicalKey::Code(KeyCode::KeyS), PhysicalKey::Code(KeyCode::KeyD), PhysicalKey::Code(KeyCode::ControlLeft), PhysicalKey::Code(KeyCode::Space), PhysicalKey::Code(KeyCode::KeyE), PhysicalKey::Code(KeyCode::KeyQ)];
         let input_manager = InputManager::new(keys, vec!["mouse".to_string()], 0);
+        let ui_manager = UiManager::new(window);
         let gamestage = GameStage::new(0);
 
-        let mut command_bus = CommandBus::new(vulkan_manager, entity_manager, input_manager, gamestage);
+        return CommandBus::new(vulkan_manager, entity_manager, input_manager, ui_manager, gamestage);
+    }
 
-        let player_transform = Transform::new(
+    //[TO-DO]: Dev scene, shared with the headless renderer so captured frames match the windowed one.
+    pub fn create_default_scene(command_bus: &mut CommandBus) {
+        let player_transform = Transform::from_euler(
             vec3(0.0, 0.0, -5.0),
             vec3(0.0, 0.0, 0.0),
+            vec3(1.0, 1.0, 1.0),
         );
         command_bus.send_command(CommandType::CreateEntity(EntityType::PlayerEntity(player_transform)));
 
-        let cube_transform1 = Transform::new(
+        let cube_transform1 = Transform::from_euler(
             vec3(-2.0, 0.0, 0.0),
             vec3(0.0, 0.0, 0.0),
+            vec3(1.0, 1.0, 1.0),
         );
-        command_bus.send_command(CommandType::CreateEntity(EntityType::CubeEntity(cube_transform1, "src/engine/vulkan/base_resources/default_texture.png".to_string())));
+        command_bus.send_command(CommandType::CreateEntity(EntityType::CubeEntity(cube_transform1, MaterialHandle::default())));
 
-        let cube_transform2 = Transform::new(
+        let cube_transform2 = Transform::from_euler(
             vec3(2.0, 0.0, 0.0),
             vec3(0.0, 0.0, 0.0),
+            vec3(1.0, 1.0, 1.0),
         );
-        command_bus.send_command(CommandType::CreateEntity(EntityType::CubeEntity(cube_transform2, "src/engine/vulkan/base_resources/default_texture.png".to_string())));
+        let tinted_material = Material { tint: vec3(1.0, 0.7, 0.5), reflectivity: 0.25, ..Material::textured("src/engine/vulkan/base_resources/default_texture.png") };
+        command_bus.send_command(CommandType::CreateMaterial(MaterialHandle::new("tinted"), tinted_material));
+        command_bus.send_command(CommandType::CreateEntity(EntityType::CubeEntity(cube_transform2, MaterialHandle::new("tinted"))));
+
+        command_bus.send_command(CommandType::SetSkybox(Some(SkyboxSource::Equirectangular("src/engine/vulkan/base_resources/default_sky.png".to_string()))));
+        command_bus.send_command(CommandType::SetAmbientLight(Vec3::splat(0.15)));
+        command_bus.send_command(CommandType::SetDirectionalLight(DirectionalLight::new(vec3(-0.4, -1.0, 0.6), vec3(1.0, 0.95, 0.85), 0.8)));
+        command_bus.send_command(CommandType::CreatePointLight(0, PointLight::new(vec3(0.0, 1.5, -1.5), vec3(0.4, 0.6, 1.0), 1.5, 6.0)));
+    }
+}
+
+impl ApplicationHandler for App {
+    //[TO-DO]: This needs to be cleaned up and have dev stuff removed from it.
+    fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+        Logger::log(LogLevel::Medium, "app", "Resumed application...");
+
+        let window_attributes = Window::default_attributes();
+        self.window = Some(event_loop.create_window(window_attributes).unwrap().into());
+        self.window.as_ref().unwrap().request_redraw();
+        self.viewport_info = Some(ViewportInfo::new(
+            [0.0, 0.0],
+            [self.window.as_ref().unwrap().inner_size().width as f32, self.window.as_ref().unwrap().inner_size().height as f32]
+        ));
+
+        let vulkan_container = VulkanContainer::new(event_loop, self.window.clone().unwrap(), self.viewport_info.as_ref().unwrap(), RenderSettings::default());
+
+        let mut command_bus = App::create_command_bus(vulkan_container, self.window.clone());
+        App::create_default_scene(&mut command_bus);
         command_bus.update_managers();
 
         self.command_bus = Some(command_bus);
@@ -64,6 +85,9 @@ impl ApplicationHandler for App {
     }
 
     fn window_event(&mut self, event_loop: &ActiveEventLoop, _id: WindowId, event: WindowEvent) {
+        //The UI sees every event first. While it is open, key presses it uses don't reach the input manager.
+        let consumed_by_ui = self.command_bus.as_mut().is_some_and(|command_bus| command_bus.ui_window_event(&event));
+
         match event {
             WindowEvent::CloseRequested => {
                 println!("The close button was pressed; stopping");
@@ -86,11 +110,34 @@ impl ApplicationHandler for App {
                 if event.physical_key == PhysicalKey::Code(KeyCode::Escape) {
                     event_loop.exit();
                 }
+                if event.physical_key == PhysicalKey::Code(KeyCode::F1) && event.state.is_pressed() && !event.repeat {
+                    self.command_bus.as_mut().unwrap().send_command(CommandType::ToggleDebugView());
+                }
+                //The cursor is only let go while the UI is open, otherwise it keeps turning the camera.
+                if event.physical_key == PhysicalKey::Code(KeyCode::F2) && event.state.is_pressed() && !event.repeat {
+                    self.command_bus.as_mut().unwrap().send_command(CommandType::ToggleUi());
+
+                    let ui_visible = self.command_bus.as_ref().unwrap().is_ui_visible();
+                    let grab_mode = if ui_visible { winit::window::CursorGrabMode::None } else { winit::window::CursorGrabMode::Locked };
+                    let window = self.window.as_ref().unwrap();
+                    if let Err(e) = window.set_cursor_grab(grab_mode) {
+                        Logger::log(LogLevel::Medium, "app", &format!("Failed to change the cursor grab: {}", e));
+                    }
+                    window.set_cursor_visible(ui_visible);
+                }
+                if event.physical_key == PhysicalKey::Code(KeyCode::F3) && event.state.is_pressed() && !event.repeat {
+                    self.command_bus.as_mut().unwrap().send_command(CommandType::ToggleHud());
+                }
                 let key_state = match event.state {
                     winit::event::ElementState::Pressed => true,
                     winit::event::ElementState::Released => false,
                 };
 
+                //Releases always go through, a key held down before the UI took focus would stay pressed otherwise.
+                //egui keeps focus after its panel is hidden, so it only gets to swallow presses while it's visible.
+                if key_state && consumed_by_ui && self.command_bus.as_ref().unwrap().is_ui_visible() {
+                    return;
+                }
                 self.command_bus.as_mut().unwrap().send_command(CommandType::KeyStateChange(event.physical_key, key_state));
             },
             _ => (),
@@ -101,6 +148,9 @@ impl ApplicationHandler for App {
     fn device_event(&mut self, _event_loop: &ActiveEventLoop, _device_id: DeviceId, event: DeviceEvent) {
         match event {
             DeviceEvent::MouseMotion { delta } => {
+                if self.command_bus.as_ref().is_some_and(|command_bus| command_bus.is_ui_visible()) {
+                    return;
+                }
                 self.command_bus.as_mut().unwrap().send_command(CommandType::AxisStateChange("mouse".to_string(), delta));
             },
             _ => {}
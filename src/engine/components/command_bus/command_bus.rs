@@ -1,12 +1,15 @@
-use glam::Vec3;
-use winit::keyboard::PhysicalKey;
+use std::sync::Arc;
 
-use crate::engine::{components::{entities::{entity::{EntityCommand, EntityType}, entity_manager::EntityManager}, gamestage::gamestage::GameStage, input_manager::input_manager::InputManager, vulkan_manager::vulkan_manager::VulkanManager}, utils::structs::transform::Transform, vulkan::structs::{vertex::Vertex, viewport::ViewportInfo}};
+use glam::{Quat, Vec3};
+use winit::{event::WindowEvent, keyboard::PhysicalKey};
+
+use crate::engine::{components::{entities::{entity::{Entity, EntityCommand, EntityType}, entity_manager::EntityManager}, gamestage::gamestage::GameStage, input_manager::input_manager::InputManager, ui_manager::ui_manager::UiManager, vulkan_manager::vulkan_manager::VulkanManager}, utils::structs::{light::{DirectionalLight, PointLight}, material::{Material, MaterialHandle}, mesh::Mesh, post_process::PostProcessChain, shadow::ShadowSettings, skybox::SkyboxSource, text::TextSection, transform::Transform}, vulkan::structs::viewport::ViewportInfo};
 
 pub struct CommandBus {
     vulkan_manager: VulkanManager,
     entity_manager: EntityManager,
     input_manager: InputManager,
+    ui_manager: UiManager,
     gamestage: GameStage,
 }
 
@@ -15,10 +18,25 @@ pub enum CommandType {
     KeyStateChange(PhysicalKey, bool),
     AxisStateChange(String, (f64, f64)),
     PlayerController(Vec3, (f64, f64), usize),
-    CreateVulkanObject(usize, Vec<Vertex>, Transform, String),
+    CreateVulkanObject(usize, Arc<Mesh>, Transform, MaterialHandle),
+    CreateMaterial(MaterialHandle, Material),
+    UpdateVulkanObjectTransform(usize, Transform),
     DeleteVulkanObject(usize),
+    SetAmbientLight(Vec3),
+    SetDirectionalLight(DirectionalLight),
+    CreatePointLight(usize, PointLight),
+    DeletePointLight(usize),
+    SetSkybox(Option<SkyboxSource>),
+    SetPostProcessChain(PostProcessChain),
+    SetShadowSettings(ShadowSettings),
+    DrawText(TextSection),
+    SetFont(String, f32),
+    ToggleHud(),
+    ToggleUi(),
     VulkanViewportResize(ViewportInfo),
     CreateEntity(EntityType),
+    SetEntityTransform(usize, Transform),
+    ToggleDebugView(),
     
     //[TO-DO]: For testing and cool purposes.
     CreateEntityForPlayer(),
@@ -26,15 +44,30 @@ pub enum CommandType {
 }
 
 impl CommandBus {
-    pub fn new(vulkan_manager: VulkanManager, entity_manager: EntityManager, input_manager: InputManager, gamestage: GameStage) -> Self {
+    pub fn new(vulkan_manager: VulkanManager, entity_manager: EntityManager, input_manager: InputManager, ui_manager: UiManager, gamestage: GameStage) -> Self {
         return CommandBus {
             vulkan_manager,
             entity_manager,
             input_manager,
+            ui_manager,
             gamestage,
         };
     }
 
+    //Window events go to the UI before anything else, returns whether the UI used the event.
+    pub fn ui_window_event(&mut self, event: &WindowEvent) -> bool {
+        return self.ui_manager.window_event(event);
+    }
+
+    pub fn is_ui_visible(&self) -> bool {
+        return self.ui_manager.is_panel_visible();
+    }
+
+    //Not a command, whoever asks for the capture needs to know whether it actually got written.
+    pub fn capture_frame(&mut self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        return self.vulkan_manager.capture_frame(path);
+    }
+
     pub fn send_command(&mut self, command: CommandType) {
         match command {
             //Input manager commands.
@@ -50,11 +83,14 @@ impl CommandBus {
 
             //Entity manager commands.
             CommandType::CreateEntity(create_info) => {self.entity_manager.create_entity(create_info);},
+            CommandType::SetEntityTransform(entity_id, transform) => {self.entity_manager.modify_entity_transform(&entity_id, transform);},
+            CommandType::ToggleDebugView() => {self.entity_manager.toggle_debug_view();},
             CommandType::CreateEntityForPlayer() => {
                 let mut front_of_player_transform = self.entity_manager.get_player_entity(0).get_transform().clone();
                 front_of_player_transform.position = -front_of_player_transform.position + front_of_player_transform.forward() * 2.0;
+                front_of_player_transform.rotation = Quat::IDENTITY;
 
-                let new_cube_info: EntityType = EntityType::CubeEntity(front_of_player_transform, "src/engine/vulkan/base_resources/default_texture.png".to_string()); 
+                let new_cube_info: EntityType = EntityType::CubeEntity(front_of_player_transform, MaterialHandle::default()); 
                 self.entity_manager.create_entity(new_cube_info);
             },
             CommandType::DeleteLastEntity() => {
@@ -68,8 +104,23 @@ impl CommandBus {
             },
 
             //Vulkan manager commands.
-            CommandType::CreateVulkanObject(object_id, vertices, object_transform, texture_path) => {self.vulkan_manager.create_vulkan_object(object_id, vertices, object_transform, texture_path.as_str());},
+            CommandType::CreateVulkanObject(object_id, mesh, object_transform, material) => {self.vulkan_manager.create_vulkan_object(object_id, mesh, object_transform, material);},
+            CommandType::CreateMaterial(handle, material) => {self.vulkan_manager.create_material(handle, &material);},
+            CommandType::UpdateVulkanObjectTransform(object_id, object_transform) => {self.vulkan_manager.update_vulkan_object_transform(object_id, object_transform);},
             CommandType::DeleteVulkanObject(object_id) => {self.vulkan_manager.delete_vulkan_object(object_id);},
+            CommandType::SetAmbientLight(ambient_light) => {self.vulkan_manager.set_ambient_light(ambient_light);},
+            CommandType::SetDirectionalLight(directional_light) => {self.vulkan_manager.set_directional_light(directional_light);},
+            CommandType::CreatePointLight(light_id, point_light) => {self.vulkan_manager.create_point_light(light_id, point_light);},
+            CommandType::DeletePointLight(light_id) => {self.vulkan_manager.delete_point_light(light_id);},
+            CommandType::SetSkybox(source) => {self.vulkan_manager.set_skybox(source);},
+            CommandType::SetPostProcessChain(post_process_chain) => {self.vulkan_manager.set_post_process_chain(post_process_chain);},
+            CommandType::SetShadowSettings(shadow_settings) => {self.vulkan_manager.set_shadow_settings(shadow_settings);},
+            CommandType::DrawText(text_section) => {self.vulkan_manager.draw_text(text_section);},
+            CommandType::SetFont(font_path, pixel_size) => {self.vulkan_manager.set_font(font_path.as_str(), pixel_size);},
+            CommandType::ToggleHud() => {self.vulkan_manager.toggle_hud();},
+
+            //UI manager commands.
+            CommandType::ToggleUi() => {self.ui_manager.toggle_panel();},
             CommandType::VulkanViewportResize(viewport_info) => {self.vulkan_manager.resize_viewport(viewport_info);},
         }
     }
@@ -83,9 +134,23 @@ impl CommandBus {
             self.send_command(command);
         }
 
+        for command in self.entity_manager.sync_transforms() {
+            self.send_command(command);
+        }
+
+        self.entity_manager.draw_debug_view();
+
+        let ui_frame = self.ui_manager.build_frame(self.vulkan_manager.get_frame_stats());
+        for command in self.ui_manager.process() {
+            self.send_command(command);
+        }
+        self.vulkan_manager.set_ui_frame(ui_frame);
+
         //[TO-DO]: This feels like spaghetti code...
+        let entity_count = self.entity_manager.get_entities().len();
         let player_entity = self.entity_manager.get_player_entity(0);
         let viewport_transform = player_entity.get_transform();
-        self.vulkan_manager.request_draw(viewport_transform.get_position(), viewport_transform.get_rotation());
+        self.vulkan_manager.draw_hud(entity_count, viewport_transform);
+        self.vulkan_manager.request_draw(viewport_transform);
     }
 }
\ No newline at end of file
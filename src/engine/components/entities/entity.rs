@@ -1,13 +1,15 @@
-use std::any::Any;
+use std::{any::Any, sync::Arc};
 
 use glam::Vec3;
 
-use crate::engine::utils::structs::{model::Model, transform::Transform};
+use crate::engine::utils::structs::{material::MaterialHandle, mesh::Mesh, transform::Transform};
 
 
 pub trait Entity: Any {
     fn get_id(&self) -> &usize;
-    fn get_model(&self) -> &Model;
+    fn get_mesh(&self) -> &Arc<Mesh>;
+    fn get_material(&self) -> &MaterialHandle;
+    fn get_transform(&self) -> &Transform;
     fn as_any_mut(&mut self) -> &mut dyn Any;
     fn recieve_command(&mut self, entity_command: EntityCommand);
 }
@@ -15,10 +17,11 @@ pub trait Entity: Any {
 #[derive(Debug)]
 pub enum EntityType {
     PlayerEntity(Transform),
-    CubeEntity(Transform, String),
+    CubeEntity(Transform, MaterialHandle),
 }
 
 pub enum EntityCommand {
     MovePlayerEntity(Vec3),
     TurnPlayerEntity(f64, f64),
+    SetTransform(Transform),
 }
\ No newline at end of file
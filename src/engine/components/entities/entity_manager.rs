@@ -1,10 +1,15 @@
-use std::collections::HashMap;
-use crate::engine::components::{command_bus::command_bus::CommandType, entities::{entity::{Entity, EntityCommand, EntityType}, subcomponents::{cube_entity::CubeEntity, player_entity::PlayerEntity}}};
+use std::collections::{HashMap, HashSet};
+use glam::{vec3, Quat, Vec3};
+use crate::engine::{components::{command_bus::command_bus::CommandType, entities::{entity::{Entity, EntityCommand, EntityType}, subcomponents::{cube_entity::CubeEntity, player_entity::PlayerEntity}}}, utils::{debug_draw::{debug_aabb, debug_axes, debug_grid, debug_sphere, DebugDrawOptions}, structs::transform::Transform}};
 
 pub struct EntityManager {
     entities: HashMap<usize, Box<dyn Entity>>,
     buffered_commands: Vec<CommandType>,
     next_id: usize,
+    //Entities which own a vulkan object, and the ones among them whose transform changed since the last sync.
+    rendered_entities: HashSet<usize>,
+    dirty_entities: HashSet<usize>,
+    debug_view: bool,
 }
 
 impl EntityManager {
@@ -13,6 +18,9 @@ impl EntityManager {
             entities: HashMap::new(),
             buffered_commands: vec![],
             next_id: 0,
+            rendered_entities: HashSet::new(),
+            dirty_entities: HashSet::new(),
+            debug_view: false,
         }
     }
 
@@ -21,10 +29,11 @@ impl EntityManager {
             EntityType::PlayerEntity(transform) => {
                 self.entities.insert(self.next_id, Box::new(PlayerEntity::new(self.next_id, transform)));
             },
-            EntityType::CubeEntity(transform, texture_path) => {
-                let cube_entity = CubeEntity::new(self.next_id, transform.clone());
-                self.buffered_commands.push(CommandType::CreateVulkanObject(self.next_id, cube_entity.get_model().get_model().clone(), transform.clone(), texture_path));
+            EntityType::CubeEntity(transform, material) => {
+                let cube_entity = CubeEntity::new(self.next_id, transform.clone(), material);
+                self.buffered_commands.push(CommandType::CreateVulkanObject(self.next_id, cube_entity.get_mesh().clone(), transform.clone(), cube_entity.get_material().clone()));
                 self.entities.insert(self.next_id, Box::new(cube_entity));
+                self.rendered_entities.insert(self.next_id);
             },
         }
 
@@ -33,6 +42,12 @@ impl EntityManager {
 
     pub fn delete_entity(&mut self, entity_id: &usize) {
         self.entities.remove(entity_id);
+        self.rendered_entities.remove(entity_id);
+        self.dirty_entities.remove(entity_id);
+    }
+
+    pub fn modify_entity_transform(&mut self, entity_id: &usize, new_transform: Transform) {
+        self.send_command(entity_id, EntityCommand::SetTransform(new_transform));
     }
 
     //[TO-DO]: This shouldnt exist.
@@ -60,5 +75,53 @@ impl EntityManager {
     pub fn send_command(&mut self, entity_id: &usize, entity_command: EntityCommand) {
         //[TO-DO]: Add error handling in case the entity ID does not exist. Seems impossible but I am ADAMANT it will at some point.
         self.entities.get_mut(entity_id).unwrap().recieve_command(entity_command);
+        self.dirty_entities.insert(*entity_id);
+    }
+
+    pub fn toggle_debug_view(&mut self) {
+        self.debug_view = !self.debug_view;
+    }
+
+    //Draws a ground grid plus the axes, bounding box and bounding sphere of every rendered entity for the current frame.
+    pub fn draw_debug_view(&self) {
+        if !self.debug_view {
+            return;
+        }
+
+        debug_grid(Vec3::ZERO, 20.0, 20, DebugDrawOptions::colored(Vec3::splat(0.4)));
+        debug_axes(&Transform::new(Vec3::ZERO, Quat::IDENTITY, Vec3::ONE), DebugDrawOptions { depth_test: false, ..Default::default() });
+
+        for entity_id in self.rendered_entities.iter() {
+            let Some(entity) = self.entities.get(entity_id) else {
+                continue;
+            };
+
+            let transform = entity.get_transform();
+            let model = transform.to_matrix();
+            let (min, max) = entity.get_mesh().get_vertices().iter()
+                .map(|vertex| model.transform_point3(vertex.get_position()))
+                .fold((Vec3::INFINITY, Vec3::NEG_INFINITY), |(min, max), point| (min.min(point), max.max(point)));
+            let bounds = entity.get_mesh().get_bounds().transformed(transform);
+
+            debug_aabb(min, max, DebugDrawOptions::colored(vec3(1.0, 0.9, 0.2)));
+            debug_sphere(bounds.center, bounds.radius, DebugDrawOptions::colored(vec3(0.2, 0.9, 1.0)));
+            debug_axes(transform, DebugDrawOptions { depth_test: false, ..Default::default() });
+        }
+    }
+
+    //Pushes the transforms of every rendered entity that changed since the last call to its vulkan object.
+    pub fn sync_transforms(&mut self) -> Vec<CommandType> {
+        let mut commands: Vec<CommandType> = vec![];
+        for entity_id in self.dirty_entities.drain() {
+            if !self.rendered_entities.contains(&entity_id) {
+                continue;
+            }
+
+            if let Some(entity) = self.entities.get(&entity_id) {
+                commands.push(CommandType::UpdateVulkanObjectTransform(entity_id, entity.get_transform().clone()));
+            }
+        }
+
+        return commands;
     }
 }
\ No newline at end of file
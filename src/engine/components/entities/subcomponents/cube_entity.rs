@@ -1,13 +1,15 @@
-use std::any::Any;
+use std::{any::Any, sync::{Arc, LazyLock}};
 
-use glam::{vec2, vec3};
+use crate::engine::{components::entities::entity::{Entity, EntityCommand}, utils::structs::{material::MaterialHandle, mesh::Mesh, transform::Transform}};
 
-use crate::engine::{components::entities::entity::{Entity, EntityCommand}, utils::structs::{model::Model, transform::Transform}, vulkan::structs::vertex::Vertex};
+//Every cube shares the same geometry, so it is only built once.
+static CUBE_MESH: LazyLock<Arc<Mesh>> = LazyLock::new(|| Arc::new(Mesh::cube()));
 
 pub struct CubeEntity {
     id: usize,
     transform: Transform,
-    model: Model,
+    mesh: Arc<Mesh>,
+    material: MaterialHandle,
 }
 
 impl Entity for CubeEntity {
@@ -15,8 +17,16 @@ impl Entity for CubeEntity {
         return &self.id;
     }
 
-    fn get_model(&self) -> &Model {
-        return &self.model;
+    fn get_mesh(&self) -> &Arc<Mesh> {
+        return &self.mesh;
+    }
+
+    fn get_material(&self) -> &MaterialHandle {
+        return &self.material;
+    }
+
+    fn get_transform(&self) -> &Transform {
+        return &self.transform;
     }
 
     fn as_any_mut(&mut self) -> &mut dyn Any {
@@ -24,72 +34,24 @@ impl Entity for CubeEntity {
     }
 
     fn recieve_command(&mut self, entity_command: EntityCommand) {
-        // Nothing to do here.
+        match entity_command {
+            EntityCommand::SetTransform(transform) => {
+                self.transform = transform;
+            },
+            _ => {
+                //[TO-DO]: Add some handling to give a heads up the command reached a reciever which doesn't handle this command
+            }
+        }
     }
 }
 
 impl CubeEntity {
-    pub fn new(id: usize, transform: Transform) -> Self {
-        let model = Model::new(vec![
-            // Front face (+Z)
-            Vertex::new(vec3(-0.5, -0.5,  0.5), vec3(255.0, 255.0, 255.0), vec2(0.0, 0.0)), // bottom-left
-            Vertex::new(vec3( 0.5,  0.5,  0.5), vec3(255.0, 255.0, 255.0), vec2(1.0, 1.0)), // top-right
-            Vertex::new(vec3( 0.5, -0.5,  0.5), vec3(255.0, 255.0, 255.0), vec2(1.0, 0.0)), // bottom-right
-
-            Vertex::new(vec3(-0.5, -0.5,  0.5), vec3(255.0, 255.0, 255.0), vec2(0.0, 0.0)), // bottom-left
-            Vertex::new(vec3(-0.5,  0.5,  0.5), vec3(255.0, 255.0, 255.0), vec2(1.0, 0.0)), // top-left
-            Vertex::new(vec3( 0.5,  0.5,  0.5), vec3(255.0, 255.0, 255.0), vec2(1.0, 1.0)), // top-right
-
-            // Back face (-Z)
-            Vertex::new(vec3( 0.5, -0.5, -0.5), vec3(255.0, 255.0, 255.0), vec2(0.0, 1.0)),
-            Vertex::new(vec3( 0.5,  0.5, -0.5), vec3(255.0, 255.0, 255.0), vec2(0.0, 0.0)),
-            Vertex::new(vec3(-0.5, -0.5, -0.5), vec3(255.0, 255.0, 255.0), vec2(1.0, 1.0)),
-
-            Vertex::new(vec3(-0.5, -0.5, -0.5), vec3(255.0, 255.0, 255.0), vec2(0.0, 1.0)),
-            Vertex::new(vec3( 0.5,  0.5, -0.5), vec3(255.0, 255.0, 255.0), vec2(1.0, 0.0)),
-            Vertex::new(vec3(-0.5,  0.5, -0.5), vec3(255.0, 255.0, 255.0), vec2(1.0, 1.0)),
-
-            // Left face (-X)
-            Vertex::new(vec3(-0.5, -0.5, -0.5), vec3(255.0, 255.0, 255.0), vec2(0.0, 0.0)),
-            Vertex::new(vec3(-0.5,  0.5,  0.5), vec3(255.0, 255.0, 255.0), vec2(1.0, 1.0)),
-            Vertex::new(vec3(-0.5, -0.5,  0.5), vec3(255.0, 255.0, 255.0), vec2(1.0, 0.0)),
-
-            Vertex::new(vec3(-0.5, -0.5, -0.5), vec3(255.0, 255.0, 255.0), vec2(0.0, 0.0)),
-            Vertex::new(vec3(-0.5,  0.5, -0.5), vec3(255.0, 255.0, 255.0), vec2(1.0, 0.0)),
-            Vertex::new(vec3(-0.5,  0.5,  0.5), vec3(255.0, 255.0, 255.0), vec2(1.0, 1.0)),
-
-            // Right face (+X)
-            Vertex::new(vec3(0.5, -0.5,  0.5), vec3(255.0, 255.0, 255.0), vec2(0.0, 0.0)),
-            Vertex::new(vec3(0.5,  0.5, -0.5), vec3(255.0, 255.0, 255.0), vec2(1.0, 1.0)),
-            Vertex::new(vec3(0.5, -0.5, -0.5), vec3(255.0, 255.0, 255.0), vec2(1.0, 0.0)),
-
-            Vertex::new(vec3(0.5, -0.5,  0.5), vec3(255.0, 255.0, 255.0), vec2(0.0, 0.0)),
-            Vertex::new(vec3(0.5,  0.5,  0.5), vec3(255.0, 255.0, 255.0), vec2(1.0, 0.0)),
-            Vertex::new(vec3(0.5,  0.5, -0.5), vec3(255.0, 255.0, 255.0), vec2(1.0, 1.0)),
-
-            // Top face (+Y)
-            Vertex::new(vec3(-0.5, 0.5,  0.5), vec3(255.0, 255.0, 255.0), vec2(0.0, 0.0)),
-            Vertex::new(vec3( 0.5, 0.5, -0.5), vec3(255.0, 255.0, 255.0), vec2(1.0, 1.0)),
-            Vertex::new(vec3( 0.5, 0.5,  0.5), vec3(255.0, 255.0, 255.0), vec2(1.0, 0.0)),
-
-            Vertex::new(vec3(-0.5, 0.5,  0.5), vec3(255.0, 255.0, 255.0), vec2(0.0, 0.0)),
-            Vertex::new(vec3(-0.5, 0.5, -0.5), vec3(255.0, 255.0, 255.0), vec2(1.0, 0.0)),
-            Vertex::new(vec3( 0.5, 0.5, -0.5), vec3(255.0, 255.0, 255.0), vec2(1.0, 1.0)),
-
-            // Bottom face (-Y)
-            Vertex::new(vec3(-0.5, -0.5, -0.5), vec3(255.0, 255.0, 255.0), vec2(0.0, 0.0)),
-            Vertex::new(vec3( 0.5, -0.5,  0.5), vec3(255.0, 255.0, 255.0), vec2(1.0, 1.0)),
-            Vertex::new(vec3( 0.5, -0.5, -0.5), vec3(255.0, 255.0, 255.0), vec2(1.0, 0.0)),
-
-            Vertex::new(vec3(-0.5, -0.5, -0.5), vec3(255.0, 255.0, 255.0), vec2(0.0, 0.0)),
-            Vertex::new(vec3(-0.5, -0.5,  0.5), vec3(255.0, 255.0, 255.0), vec2(1.0, 0.0)),
-            Vertex::new(vec3( 0.5, -0.5,  0.5), vec3(255.0, 255.0, 255.0), vec2(1.0, 1.0)),
-        ]);
-        
+    pub fn new(id: usize, transform: Transform, material: MaterialHandle) -> Self {
         return CubeEntity { 
             id,
             transform,
-            model
+            mesh: CUBE_MESH.clone(),
+            material,
         }
     }
 }
\ No newline at end of file
@@ -1,24 +1,35 @@
-use std::{any::Any, default};
+use std::{any::Any, default, sync::Arc};
 
-use crate::engine::{components::entities::entity::{Entity, EntityCommand}, utils::structs::{model::Model, transform::Transform}};
+use glam::Vec3;
+
+use crate::engine::{components::entities::entity::{Entity, EntityCommand}, utils::structs::{material::MaterialHandle, mesh::Mesh, transform::Transform}};
 
 #[derive(Debug)]
 pub struct PlayerEntity {
     id: usize,
     transform: Transform,
-    model: Model,
+    mesh: Arc<Mesh>,
+    material: MaterialHandle,
     speed: f32,
     sensitivity: f32,
+    //Camera angles are kept as euler angles so looking around never introduces roll.
+    pitch: f32,
+    yaw: f32,
 }
 
 impl PlayerEntity {
     pub fn new(id: usize, transform: Transform) -> Self {
+        let euler = transform.get_euler();
+
         return PlayerEntity{
             id,
             transform,
-            model: Model::new(vec![]),
+            mesh: Arc::new(Mesh::empty()),
+            material: MaterialHandle::default(),
             speed: 0.03f32,
             sensitivity: 0.001f32,
+            pitch: euler.x,
+            yaw: euler.y,
         };
     }
 
@@ -26,13 +37,6 @@ impl PlayerEntity {
         return &self.id;
     }
 
-    pub fn get_transform(&self) -> &Transform {
-        return &self.transform;
-    }
-
-    pub fn modify_transform(&mut self, new_transform: Transform) {
-        self.transform = new_transform;
-    }
 }
 
 impl Entity for PlayerEntity {
@@ -40,8 +44,16 @@ impl Entity for PlayerEntity {
         return &self.id;
     }
 
-    fn get_model(&self) -> &Model {
-        return &self.model;
+    fn get_mesh(&self) -> &Arc<Mesh> {
+        return &self.mesh;
+    }
+
+    fn get_material(&self) -> &MaterialHandle {
+        return &self.material;
+    }
+
+    fn get_transform(&self) -> &Transform {
+        return &self.transform;
     }
 
     fn as_any_mut(&mut self) -> &mut dyn Any {
@@ -67,12 +79,18 @@ impl Entity for PlayerEntity {
                 let mut new_transform: Transform = self.transform.clone();
 
                 //Camera
-                new_transform.rotation.y += y as f32 * self.sensitivity;
-                new_transform.rotation.x += x as f32 * -self.sensitivity;
-                new_transform.rotation.x = new_transform.get_rotation().x.clamp(-1.5, 1.5);
+                self.yaw += y as f32 * self.sensitivity;
+                self.pitch = (self.pitch + x as f32 * -self.sensitivity).clamp(-1.5, 1.5);
+                new_transform.set_euler(Vec3::new(self.pitch, self.yaw, 0.0));
 
                 self.transform = new_transform;
             },
+            EntityCommand::SetTransform(transform) => {
+                let euler = transform.get_euler();
+                self.pitch = euler.x;
+                self.yaw = euler.y;
+                self.transform = transform;
+            },
             default => {
                 //[TO-DO]: Add some handling to give a heads up the command reached a reciever which doesn't handle this command
             }
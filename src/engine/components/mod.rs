@@ -2,4 +2,5 @@ pub mod gamestage;
 pub mod input_manager;
 pub mod command_bus;
 pub mod entities;
-pub mod vulkan_manager;
\ No newline at end of file
+pub mod vulkan_manager;
+pub mod ui_manager;
\ No newline at end of file
@@ -0,0 +1 @@
+pub mod ui_manager;
\ No newline at end of file
@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use egui::{Context, RawInput, ViewportId};
+use glam::Vec3;
+use winit::{event::WindowEvent, window::Window};
+
+use crate::engine::{components::command_bus::command_bus::CommandType, vulkan::structs::{frame_stats::FrameStats, ui_frame::UiFrame}};
+
+//Immediate mode UI drawn on top of everything. Panels are rebuilt every frame and anything they change goes out as commands.
+pub struct UiManager {
+    context: Context,
+    window: Option<Arc<Window>>,
+    state: Option<egui_winit::State>,
+    panel_visible: bool,
+    ambient_light: f32,
+    commands: Vec<CommandType>,
+}
+
+impl UiManager {
+    //Headless UI managers have no window to take input from, they still build and paint their panels.
+    pub fn new(window: Option<Arc<Window>>) -> Self {
+        let context = Context::default();
+        let state = window.as_ref().map(|window| egui_winit::State::new(context.clone(), ViewportId::ROOT, window.as_ref(), Some(window.scale_factor() as f32), window.theme(), None));
+
+        return UiManager {
+            context,
+            window,
+            state,
+            panel_visible: false,
+            ambient_light: 0.15,
+            commands: vec![],
+        };
+    }
+
+    //Returns whether the UI used the event, used events shouldn't be handled by anything else.
+    pub fn window_event(&mut self, event: &WindowEvent) -> bool {
+        let (Some(state), Some(window)) = (self.state.as_mut(), self.window.as_ref()) else {
+            return false;
+        };
+
+        return state.on_window_event(window, event).consumed;
+    }
+
+    pub fn toggle_panel(&mut self) {
+        self.panel_visible = !self.panel_visible;
+    }
+
+    pub fn is_panel_visible(&self) -> bool {
+        return self.panel_visible;
+    }
+
+    pub fn build_frame(&mut self, frame_stats: &FrameStats) -> UiFrame {
+        let raw_input = match (self.state.as_mut(), self.window.as_ref()) {
+            (Some(state), Some(window)) => state.take_egui_input(window),
+            _ => RawInput::default(),
+        };
+
+        let context = self.context.clone();
+        let full_output = context.run(raw_input, |context| {
+            if self.panel_visible {
+                self.debug_panel(context, frame_stats);
+            }
+        });
+
+        if let (Some(state), Some(window)) = (self.state.as_mut(), self.window.as_ref()) {
+            state.handle_platform_output(window, full_output.platform_output);
+        }
+
+        return UiFrame {
+            primitives: self.context.tessellate(full_output.shapes, full_output.pixels_per_point),
+            textures_delta: full_output.textures_delta,
+            pixels_per_point: full_output.pixels_per_point,
+        };
+    }
+
+    fn debug_panel(&mut self, context: &Context, frame_stats: &FrameStats) {
+        egui::Window::new("Debug").default_pos([12.0, 140.0]).resizable(false).show(context, |ui| {
+            ui.label(format!("Objects: {} drawn, {} culled", frame_stats.objects, frame_stats.culled_objects));
+            ui.label(format!("Draw calls: {}", frame_stats.draw_calls));
+            ui.label(format!("Binds: {} pipelines, {} materials", frame_stats.pipeline_binds, frame_stats.material_binds));
+            ui.label(format!("Recording: {:.3} ms", frame_stats.recording_time.as_secs_f64() * 1000.0));
+            ui.separator();
+
+            if ui.add(egui::Slider::new(&mut self.ambient_light, 0.0..=1.0).text("Ambient light")).changed() {
+                self.commands.push(CommandType::SetAmbientLight(Vec3::splat(self.ambient_light)));
+            }
+
+            ui.horizontal(|ui| {
+                if ui.button("HUD (F3)").clicked() {
+                    self.commands.push(CommandType::ToggleHud());
+                }
+                if ui.button("Debug view (F1)").clicked() {
+                    self.commands.push(CommandType::ToggleDebugView());
+                }
+            });
+        });
+    }
+
+    pub fn process(&mut self) -> Vec<CommandType> {
+        return std::mem::take(&mut self.commands);
+    }
+}
\ No newline at end of file
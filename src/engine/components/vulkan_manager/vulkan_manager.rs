@@ -1,31 +1,133 @@
-use glam::Vec3;
+use std::{sync::Arc, time::Instant};
 
-use crate::engine::{utils::structs::transform::Transform, vulkan::{structs::{vertex::Vertex, viewport::ViewportInfo}, vulkan_container::VulkanContainer}};
+use glam::{vec2, vec4, Vec3};
+
+use crate::engine::{utils::{logger::{LogLevel, Logger}, structs::{light::{DirectionalLight, PointLight}, material::{Material, MaterialHandle}, mesh::Mesh, post_process::PostProcessChain, shadow::ShadowSettings, skybox::SkyboxSource, text::TextSection, transform::Transform}}, vulkan::{structs::{frame_stats::FrameStats, ui_frame::UiFrame, viewport::ViewportInfo}, vulkan_container::VulkanContainer}};
+
+//How much of the newest frame time goes into the smoothed one shown on the HUD, lower is steadier.
+const FRAME_TIME_SMOOTHING: f32 = 0.1;
 
 pub struct VulkanManager {
     vulkan_container: VulkanContainer,
+    hud_visible: bool,
+    last_frame: Option<Instant>,
+    smoothed_frame_time: f32,
 }
 
 impl VulkanManager {
     pub fn new(vulkan_container: VulkanContainer) -> Self {
         return VulkanManager {
-            vulkan_container
+            vulkan_container,
+            hud_visible: false,
+            last_frame: None,
+            smoothed_frame_time: 0.0,
+        }
+    }
+
+    pub fn request_draw(&mut self, viewport_transform: &Transform) {
+        let now = Instant::now();
+        if let Some(last_frame) = self.last_frame {
+            let frame_time = (now - last_frame).as_secs_f32();
+            self.smoothed_frame_time = if self.smoothed_frame_time == 0.0 { frame_time } else { self.smoothed_frame_time + (frame_time - self.smoothed_frame_time) * FRAME_TIME_SMOOTHING };
+        }
+        self.last_frame = Some(now);
+
+        self.vulkan_container.draw_frame(viewport_transform);
+    }
+
+    pub fn toggle_hud(&mut self) {
+        self.hud_visible = !self.hud_visible;
+    }
+
+    //Frame numbers are from the last recorded frame, the HUD for this one is queued before it gets recorded.
+    pub fn draw_hud(&mut self, entity_count: usize, viewport_transform: &Transform) {
+        if !self.hud_visible {
+            return;
         }
+
+        let frames_per_second = if self.smoothed_frame_time > 0.0 { 1.0 / self.smoothed_frame_time } else { 0.0 };
+        let camera_position = viewport_transform.get_camera_position();
+        let frame_stats = self.vulkan_container.get_frame_stats();
+        let text = format!(
+            "FPS: {:.0} ({:.2} ms)\nPosition: {:.2} {:.2} {:.2}\nEntities: {}\nObjects: {} drawn, {} culled\nDraw calls: {}",
+            frames_per_second, self.smoothed_frame_time * 1000.0,
+            camera_position.x, camera_position.y, camera_position.z,
+            entity_count,
+            frame_stats.objects, frame_stats.culled_objects,
+            frame_stats.draw_calls,
+        );
+
+        self.vulkan_container.draw_text(TextSection { color: vec4(1.0, 1.0, 0.6, 1.0), ..TextSection::new(&text, vec2(8.0, 8.0)) });
+    }
+
+    pub fn set_ui_frame(&mut self, ui_frame: UiFrame) {
+        self.vulkan_container.set_ui_frame(ui_frame);
     }
 
-    pub fn request_draw(&mut self, viewport_location: Vec3, viewport_rotation: Vec3) {
-        self.vulkan_container.draw_frame(viewport_location, viewport_rotation);
+    pub fn get_frame_stats(&self) -> &FrameStats {
+        return self.vulkan_container.get_frame_stats();
     }
 
-    pub fn create_vulkan_object(&mut self, object_id: usize, vertices: Vec<Vertex>, object_transform: Transform, texture_path: &str) {
-        self.vulkan_container.create_vulkan_object(object_id, vertices, object_transform, texture_path);
+    pub fn draw_text(&mut self, text_section: TextSection) {
+        self.vulkan_container.draw_text(text_section);
+    }
+
+    pub fn set_font(&mut self, font_path: &str, pixel_size: f32) {
+        self.vulkan_container.set_font(font_path, pixel_size);
+    }
+
+    pub fn create_vulkan_object(&mut self, object_id: usize, mesh: Arc<Mesh>, object_transform: Transform, material: MaterialHandle) {
+        self.vulkan_container.create_vulkan_object(object_id, &mesh, object_transform, material);
+    }
+
+    pub fn create_material(&mut self, handle: MaterialHandle, material: &Material) {
+        self.vulkan_container.create_material(handle, material);
+    }
+
+    pub fn update_vulkan_object_transform(&mut self, object_id: usize, object_transform: Transform) {
+        self.vulkan_container.update_vulkan_object_transform(object_id, object_transform);
     }
 
     pub fn delete_vulkan_object(&mut self, object_id: usize) {
         self.vulkan_container.delete_vulkan_object(object_id);
     }
 
+    pub fn set_ambient_light(&mut self, ambient_light: Vec3) {
+        self.vulkan_container.set_ambient_light(ambient_light);
+    }
+
+    pub fn set_directional_light(&mut self, directional_light: DirectionalLight) {
+        self.vulkan_container.set_directional_light(directional_light);
+    }
+
+    pub fn set_skybox(&mut self, source: Option<SkyboxSource>) {
+        self.vulkan_container.set_skybox(source.as_ref());
+    }
+
+    pub fn set_post_process_chain(&mut self, post_process_chain: PostProcessChain) {
+        self.vulkan_container.set_post_process_chain(post_process_chain);
+    }
+
+    pub fn set_shadow_settings(&mut self, shadow_settings: ShadowSettings) {
+        self.vulkan_container.set_shadow_settings(shadow_settings);
+    }
+
+    pub fn create_point_light(&mut self, light_id: usize, point_light: PointLight) {
+        self.vulkan_container.create_point_light(light_id, point_light);
+    }
+
+    pub fn delete_point_light(&mut self, light_id: usize) {
+        self.vulkan_container.delete_point_light(light_id);
+    }
+
     pub fn resize_viewport(&mut self, viewport_info: ViewportInfo) {
         self.vulkan_container.resize_viewport(&viewport_info);
     }
+
+    pub fn capture_frame(&mut self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        return self.vulkan_container.save_frame_png(path).map_err(|error| {
+            Logger::log(LogLevel::High, "vulkan_manager", &format!("Failed to capture frame to {}: {}", path, error));
+            error
+        });
+    }
 }
\ No newline at end of file
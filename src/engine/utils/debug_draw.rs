@@ -0,0 +1,195 @@
+use std::{f32::consts::TAU, sync::Mutex, time::{Duration, Instant}};
+
+use glam::{vec3, Vec3};
+
+use crate::engine::utils::structs::transform::Transform;
+
+//Segments per circle of a debug sphere.
+const SPHERE_SEGMENTS: usize = 24;
+
+//Lines queued by any system, the renderer takes them out every frame.
+static DEBUG_DRAW_QUEUE: Mutex<DebugDrawQueue> = Mutex::new(DebugDrawQueue::new());
+
+#[derive(Clone, Copy, Debug)]
+pub struct DebugDrawOptions {
+    pub color: Vec3,
+    //How long the shape stays on screen, zero draws it for a single frame.
+    pub duration: Duration,
+    //Without depth testing the shape shows through everything in front of it.
+    pub depth_test: bool,
+}
+
+impl DebugDrawOptions {
+    pub fn colored(color: Vec3) -> Self {
+        return DebugDrawOptions { color, ..Default::default() };
+    }
+}
+
+impl Default for DebugDrawOptions {
+    fn default() -> Self {
+        return DebugDrawOptions { color: Vec3::ONE, duration: Duration::ZERO, depth_test: true };
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DebugLine {
+    pub start: Vec3,
+    pub end: Vec3,
+    pub color: Vec3,
+    pub depth_test: bool,
+}
+
+struct DebugDrawQueue {
+    lines: Vec<(DebugLine, Instant)>,
+}
+
+impl DebugDrawQueue {
+    const fn new() -> Self {
+        return DebugDrawQueue { lines: Vec::new() };
+    }
+
+    fn push(&mut self, segments: &[(Vec3, Vec3)], options: &DebugDrawOptions, now: Instant) {
+        let expires_at = now.checked_add(options.duration).unwrap_or(now);
+        for (start, end) in segments.iter() {
+            self.lines.push((DebugLine { start: *start, end: *end, color: options.color, depth_test: options.depth_test }, expires_at));
+        }
+    }
+
+    //Every queued line is drawn at least once, only the ones whose duration hasn't run out stay for the next frame.
+    fn take(&mut self, now: Instant) -> Vec<DebugLine> {
+        let lines = self.lines.iter().map(|(line, _)| *line).collect();
+        self.lines.retain(|(_, expires_at)| *expires_at > now);
+
+        return lines;
+    }
+}
+
+pub fn debug_line(start: Vec3, end: Vec3, options: DebugDrawOptions) {
+    queue_segments(&[(start, end)], &options);
+}
+
+pub fn debug_aabb(min: Vec3, max: Vec3, options: DebugDrawOptions) {
+    queue_segments(&aabb_segments(min, max), &options);
+}
+
+//Three circles, one around each axis.
+pub fn debug_sphere(center: Vec3, radius: f32, options: DebugDrawOptions) {
+    queue_segments(&sphere_segments(center, radius), &options);
+}
+
+//The local X, Y and Z axes of the transform in red, green and blue, scaled along with it. The color in the options is ignored.
+pub fn debug_axes(transform: &Transform, options: DebugDrawOptions) {
+    let matrix = transform.to_matrix();
+    let origin = matrix.transform_point3(Vec3::ZERO);
+
+    for (axis, color) in [(Vec3::X, vec3(1.0, 0.0, 0.0)), (Vec3::Y, vec3(0.0, 1.0, 0.0)), (Vec3::Z, vec3(0.0, 0.0, 1.0))] {
+        debug_line(origin, matrix.transform_point3(axis), DebugDrawOptions { color, ..options });
+    }
+}
+
+//A flat grid on the XZ plane with the given amount of cells along each side.
+pub fn debug_grid(center: Vec3, size: f32, cells: u32, options: DebugDrawOptions) {
+    queue_segments(&grid_segments(center, size, cells), &options);
+}
+
+//Called by the renderer once per frame.
+pub fn take_debug_lines(now: Instant) -> Vec<DebugLine> {
+    return DEBUG_DRAW_QUEUE.lock().unwrap().take(now);
+}
+
+fn queue_segments(segments: &[(Vec3, Vec3)], options: &DebugDrawOptions) {
+    DEBUG_DRAW_QUEUE.lock().unwrap().push(segments, options, Instant::now());
+}
+
+fn aabb_segments(min: Vec3, max: Vec3) -> Vec<(Vec3, Vec3)> {
+    let corner = |index: usize| vec3(
+        if index & 1 == 0 { min.x } else { max.x },
+        if index & 2 == 0 { min.y } else { max.y },
+        if index & 4 == 0 { min.z } else { max.z },
+    );
+
+    //Corners one bit apart share an edge.
+    let mut segments = vec![];
+    for index in 0..8 {
+        for bit in [1, 2, 4] {
+            if index & bit == 0 {
+                segments.push((corner(index), corner(index | bit)));
+            }
+        }
+    }
+
+    return segments;
+}
+
+fn sphere_segments(center: Vec3, radius: f32) -> Vec<(Vec3, Vec3)> {
+    let mut segments = vec![];
+    for (u, v) in [(Vec3::X, Vec3::Y), (Vec3::Y, Vec3::Z), (Vec3::Z, Vec3::X)] {
+        let point = |segment: usize| {
+            let angle = segment as f32 / SPHERE_SEGMENTS as f32 * TAU;
+            return center + (u * angle.cos() + v * angle.sin()) * radius;
+        };
+
+        for segment in 0..SPHERE_SEGMENTS {
+            segments.push((point(segment), point(segment + 1)));
+        }
+    }
+
+    return segments;
+}
+
+fn grid_segments(center: Vec3, size: f32, cells: u32) -> Vec<(Vec3, Vec3)> {
+    let half_size = size * 0.5;
+    let cells = cells.max(1);
+
+    let mut segments = vec![];
+    for line in 0..=cells {
+        let offset = line as f32 / cells as f32 * size - half_size;
+        segments.push((center + vec3(offset, 0.0, -half_size), center + vec3(offset, 0.0, half_size)));
+        segments.push((center + vec3(-half_size, 0.0, offset), center + vec3(half_size, 0.0, offset)));
+    }
+
+    return segments;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 1e-5;
+
+    #[test]
+    fn aabb_has_twelve_axis_aligned_edges_of_the_right_length() {
+        let segments = aabb_segments(vec3(-1.0, 0.0, 2.0), vec3(1.0, 3.0, 6.0));
+
+        assert_eq!(segments.len(), 12);
+        let mut lengths: Vec<f32> = segments.iter().map(|(start, end)| start.distance(*end)).collect();
+        lengths.sort_by(f32::total_cmp);
+        assert_eq!(lengths, vec![2.0, 2.0, 2.0, 2.0, 3.0, 3.0, 3.0, 3.0, 4.0, 4.0, 4.0, 4.0]);
+    }
+
+    #[test]
+    fn sphere_points_lie_on_the_sphere() {
+        let center = vec3(1.0, -2.0, 0.5);
+        let segments = sphere_segments(center, 3.0);
+
+        assert_eq!(segments.len(), 3 * SPHERE_SEGMENTS);
+        assert!(segments.iter().all(|(start, end)| (start.distance(center) - 3.0).abs() < EPSILON && (end.distance(center) - 3.0).abs() < EPSILON));
+    }
+
+    #[test]
+    fn lines_stay_queued_for_their_duration() {
+        let mut queue = DebugDrawQueue::new();
+        let start = Instant::now();
+        queue.push(&[(Vec3::ZERO, Vec3::X)], &DebugDrawOptions::default(), start);
+        queue.push(&[(Vec3::ZERO, Vec3::Y)], &DebugDrawOptions { duration: Duration::from_secs(1), depth_test: false, ..Default::default() }, start);
+
+        //Both are drawn on the first frame, even the one without a duration.
+        let first_frame = queue.take(start);
+        assert_eq!(first_frame.len(), 2);
+        assert!(!first_frame[1].depth_test);
+
+        assert_eq!(queue.take(start + Duration::from_millis(500)), vec![DebugLine { start: Vec3::ZERO, end: Vec3::Y, color: Vec3::ONE, depth_test: false }]);
+        assert_eq!(queue.take(start + Duration::from_secs(1)).len(), 1);
+        assert!(queue.take(start + Duration::from_secs(2)).is_empty());
+    }
+}
\ No newline at end of file
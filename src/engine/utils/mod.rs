@@ -1,2 +1,3 @@
 pub mod logger;
-pub mod structs;
\ No newline at end of file
+pub mod structs;
+pub mod debug_draw;
\ No newline at end of file
@@ -0,0 +1,65 @@
+use glam::Vec3;
+
+use crate::engine::utils::structs::transform::Transform;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoundingSphere {
+    pub center: Vec3,
+    pub radius: f32,
+}
+
+impl BoundingSphere {
+    pub fn new(center: Vec3, radius: f32) -> Self {
+        return BoundingSphere { center, radius };
+    }
+
+    //Centered on the middle of the points' bounding box, not the tightest sphere but good enough for culling.
+    pub fn from_points(points: impl Iterator<Item = Vec3> + Clone) -> Self {
+        let (min, max) = points.clone().fold((Vec3::INFINITY, Vec3::NEG_INFINITY), |(min, max), point| (min.min(point), max.max(point)));
+        if min.x > max.x {
+            return BoundingSphere::new(Vec3::ZERO, 0.0);
+        }
+
+        let center = (min + max) * 0.5;
+        let radius = points.map(|point| point.distance(center)).fold(0.0, f32::max);
+
+        return BoundingSphere::new(center, radius);
+    }
+
+    //Moves a local space sphere into world space. Non uniform scale grows the sphere by the largest axis so it always stays conservative.
+    pub fn transformed(&self, transform: &Transform) -> Self {
+        let center = transform.to_matrix().transform_point3(self.center);
+        let radius = self.radius * transform.scale.abs().max_element();
+
+        return BoundingSphere::new(center, radius);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{vec3, Vec3};
+
+    use super::BoundingSphere;
+    use crate::engine::utils::structs::transform::Transform;
+
+    #[test]
+    fn sphere_encloses_all_points() {
+        let points = vec![vec3(-0.5, -0.5, -0.5), vec3(0.5, 0.5, 0.5), vec3(0.5, -0.5, 0.5)];
+        let sphere = BoundingSphere::from_points(points.iter().cloned());
+
+        assert!(sphere.center.abs_diff_eq(Vec3::ZERO, 1e-6));
+        for point in points {
+            assert!(point.distance(sphere.center) <= sphere.radius + 1e-6);
+        }
+    }
+
+    #[test]
+    fn transformed_sphere_follows_position_and_largest_scale() {
+        let sphere = BoundingSphere::new(vec3(1.0, 0.0, 0.0), 1.0);
+        let transform = Transform::from_euler(vec3(0.0, 5.0, 0.0), Vec3::ZERO, vec3(2.0, 1.0, -3.0));
+        let world_sphere = sphere.transformed(&transform);
+
+        assert!(world_sphere.center.abs_diff_eq(vec3(2.0, 5.0, 0.0), 1e-6));
+        assert_eq!(world_sphere.radius, 3.0);
+    }
+}
\ No newline at end of file
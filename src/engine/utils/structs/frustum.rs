@@ -0,0 +1,110 @@
+use glam::{Mat4, Vec4};
+
+use crate::engine::utils::structs::bounding_sphere::BoundingSphere;
+
+//The six clip planes of a view projection, stored as (normal, distance) with the normals pointing into the frustum.
+#[derive(Clone, Copy, Debug)]
+pub struct Frustum {
+    planes: [Vec4; 6],
+}
+
+impl Frustum {
+    //Gribb/Hartmann plane extraction. The projection comes from perspective_rh_gl so clip space depth runs from -w to w.
+    pub fn from_view_projection(view_projection: Mat4) -> Self {
+        let row_x = view_projection.row(0);
+        let row_y = view_projection.row(1);
+        let row_z = view_projection.row(2);
+        let row_w = view_projection.row(3);
+
+        let planes = [
+            row_w + row_x, // left
+            row_w - row_x, // right
+            row_w + row_y, // bottom
+            row_w - row_y, // top
+            row_w + row_z, // near
+            row_w - row_z, // far
+        ].map(|plane| plane / plane.truncate().length());
+
+        return Frustum { planes };
+    }
+
+    pub fn get_planes(&self) -> &[Vec4; 6] {
+        return &self.planes;
+    }
+
+    pub fn intersects_sphere(&self, sphere: &BoundingSphere) -> bool {
+        return self.planes.iter().all(|plane| plane.truncate().dot(sphere.center) + plane.w >= -sphere.radius);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{vec3, Mat4, Vec3};
+
+    use super::Frustum;
+    use crate::engine::utils::structs::{bounding_sphere::BoundingSphere, transform::Transform};
+
+    fn camera_frustum() -> Frustum {
+        //Camera at the origin looking down -Z, 90 degree vertical field of view.
+        let projection = Mat4::perspective_rh_gl(90.0_f32.to_radians(), 1.0, 0.1, 100.0);
+        return Frustum::from_view_projection(projection);
+    }
+
+    #[test]
+    fn planes_are_normalized_and_match_the_projection() {
+        let frustum = camera_frustum();
+        for plane in frustum.get_planes() {
+            assert!((plane.truncate().length() - 1.0).abs() < 1e-5);
+        }
+
+        let [left, right, bottom, top, near, far] = *frustum.get_planes();
+        let diagonal = std::f32::consts::FRAC_1_SQRT_2;
+
+        assert!(left.truncate().abs_diff_eq(vec3(diagonal, 0.0, -diagonal), 1e-5));
+        assert!(right.truncate().abs_diff_eq(vec3(-diagonal, 0.0, -diagonal), 1e-5));
+        assert!(bottom.truncate().abs_diff_eq(vec3(0.0, diagonal, -diagonal), 1e-5));
+        assert!(top.truncate().abs_diff_eq(vec3(0.0, -diagonal, -diagonal), 1e-5));
+        assert!(near.truncate().abs_diff_eq(Vec3::NEG_Z, 1e-5));
+        assert!((near.w + 0.1).abs() < 1e-4);
+        assert!(far.truncate().abs_diff_eq(Vec3::Z, 1e-5));
+        assert!((far.w - 100.0).abs() < 1e-1);
+    }
+
+    #[test]
+    fn spheres_are_culled_by_each_plane() {
+        let frustum = camera_frustum();
+
+        assert!(frustum.intersects_sphere(&BoundingSphere::new(vec3(0.0, 0.0, -10.0), 1.0)));
+
+        assert!(!frustum.intersects_sphere(&BoundingSphere::new(vec3(0.0, 0.0, 10.0), 1.0)), "behind the camera");
+        assert!(!frustum.intersects_sphere(&BoundingSphere::new(vec3(0.0, 0.0, -200.0), 1.0)), "past the far plane");
+        assert!(!frustum.intersects_sphere(&BoundingSphere::new(vec3(-20.0, 0.0, -10.0), 1.0)), "left of the frustum");
+        assert!(!frustum.intersects_sphere(&BoundingSphere::new(vec3(20.0, 0.0, -10.0), 1.0)), "right of the frustum");
+        assert!(!frustum.intersects_sphere(&BoundingSphere::new(vec3(0.0, -20.0, -10.0), 1.0)), "below the frustum");
+        assert!(!frustum.intersects_sphere(&BoundingSphere::new(vec3(0.0, 20.0, -10.0), 1.0)), "above the frustum");
+    }
+
+    #[test]
+    fn spheres_straddling_a_plane_are_kept() {
+        let frustum = camera_frustum();
+
+        //The center is just outside the left plane but the radius reaches back in.
+        assert!(frustum.intersects_sphere(&BoundingSphere::new(vec3(-10.5, 0.0, -10.0), 1.0)));
+        assert!(!frustum.intersects_sphere(&BoundingSphere::new(vec3(-12.0, 0.0, -10.0), 1.0)));
+    }
+
+    #[test]
+    fn frustum_follows_the_camera_transform() {
+        let projection = Mat4::perspective_rh_gl(45.0_f32.to_radians(), 1.0, 0.1, 1000.0);
+        let camera = Transform::from_euler(vec3(0.0, 0.0, -5.0), vec3(0.0, std::f32::consts::PI, 0.0), Vec3::ONE);
+        let frustum = Frustum::from_view_projection(projection * camera.to_view_matrix());
+
+        //The camera sits at z = 5 in the world and has turned around, so it now looks down +Z.
+        let in_front = BoundingSphere::new(vec3(0.0, 0.0, 15.0), 0.5);
+        let behind = BoundingSphere::new(vec3(0.0, 0.0, -5.0), 0.5);
+
+        assert!(frustum.intersects_sphere(&in_front));
+        assert!(!frustum.intersects_sphere(&behind));
+        assert!(camera.forward().abs_diff_eq(Vec3::Z, 1e-5));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,36 @@
+use glam::{vec3, Vec3};
+
+//Sun like light that hits everything from the same direction, the direction points from the light into the scene.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DirectionalLight {
+    pub direction: Vec3,
+    pub color: Vec3,
+    pub intensity: f32,
+}
+
+impl DirectionalLight {
+    pub fn new(direction: Vec3, color: Vec3, intensity: f32) -> Self {
+        return DirectionalLight { direction: direction.normalize_or(Vec3::NEG_Y), color, intensity };
+    }
+}
+
+impl Default for DirectionalLight {
+    fn default() -> Self {
+        return DirectionalLight::new(vec3(-0.4, -1.0, -0.6), Vec3::ONE, 1.0);
+    }
+}
+
+//Light shining in every direction from a point, fading out to nothing at its range.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PointLight {
+    pub position: Vec3,
+    pub color: Vec3,
+    pub intensity: f32,
+    pub range: f32,
+}
+
+impl PointLight {
+    pub fn new(position: Vec3, color: Vec3, intensity: f32, range: f32) -> Self {
+        return PointLight { position, color, intensity, range };
+    }
+}
\ No newline at end of file
@@ -0,0 +1,74 @@
+use glam::{Vec3, Vec4};
+use vulkano::image::sampler::{Filter, SamplerAddressMode};
+
+//Materials are registered with the renderer under a name, entities only carry the handle around.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MaterialHandle(String);
+
+impl MaterialHandle {
+    pub fn new(name: &str) -> Self {
+        return MaterialHandle(name.to_string());
+    }
+
+    pub fn get_name(&self) -> &str {
+        return &self.0;
+    }
+}
+
+//The renderer always has a material under this handle, anything pointing at a missing material falls back to it.
+impl Default for MaterialHandle {
+    fn default() -> Self {
+        return MaterialHandle::new("default");
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SamplerSettings {
+    pub filter: Filter,
+    pub address_mode: SamplerAddressMode,
+}
+
+impl Default for SamplerSettings {
+    fn default() -> Self {
+        return SamplerSettings { filter: Filter::Linear, address_mode: SamplerAddressMode::Repeat };
+    }
+}
+
+//Describes how a surface looks. The final color is the base color texture times the base color times the tint,
+//without a texture the base color is used as is.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Material {
+    pub base_color: Vec4,
+    pub tint: Vec3,
+    pub base_color_texture: Option<String>,
+    pub sampler: SamplerSettings,
+    //How much of the skybox the surface mirrors, from 0 for none to 1 for a perfect mirror.
+    pub reflectivity: f32,
+    //Light the surface gives off by itself, in linear HDR units so anything brighter than the bloom threshold glows.
+    pub emissive: Vec3,
+    //How much of the vertex color is given off as light on top of the emissive color.
+    pub vertex_color_emission: f32,
+    //Name of a pipeline registered in the renderer, such as opaque, alpha_blended, wireframe or unlit.
+    pub pipeline: String,
+}
+
+impl Material {
+    pub fn textured(texture_path: &str) -> Self {
+        return Material { base_color_texture: Some(texture_path.to_string()), ..Default::default() };
+    }
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        return Material {
+            base_color: Vec4::ONE,
+            tint: Vec3::ONE,
+            base_color_texture: None,
+            sampler: SamplerSettings::default(),
+            reflectivity: 0.0,
+            emissive: Vec3::ZERO,
+            vertex_color_emission: 0.0,
+            pipeline: "opaque".to_string(),
+        };
+    }
+}
\ No newline at end of file
@@ -0,0 +1,115 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use glam::{vec2, Vec3};
+
+use crate::engine::{utils::structs::bounding_sphere::BoundingSphere, vulkan::structs::vertex::Vertex};
+
+//CPU side geometry. Meshes with the same name and the same geometry share one set of GPU buffers.
+#[derive(Debug)]
+pub struct Mesh {
+    name: String,
+    vertices: Vec<Vertex>,
+    indices: Vec<u32>,
+    //Hashed once up front, meshes never change after being built.
+    content_hash: u64,
+}
+
+impl Mesh {
+    pub fn new(name: &str, vertices: Vec<Vertex>, indices: Vec<u32>) -> Self {
+        let mut hasher = DefaultHasher::new();
+        bytemuck::cast_slice::<Vertex, u8>(&vertices).hash(&mut hasher);
+        indices.hash(&mut hasher);
+
+        return Mesh { name: name.to_string(), vertices, indices, content_hash: hasher.finish() };
+    }
+
+    pub fn empty() -> Self {
+        return Mesh::new("empty", vec![], vec![]);
+    }
+
+    pub fn cube() -> Self {
+        //Every face is described by its normal and the two axes spanning it, picked so that u x v == normal which keeps the winding counter clockwise from outside.
+        let faces = [
+            (Vec3::Z, Vec3::X, Vec3::Y),
+            (Vec3::NEG_Z, Vec3::NEG_X, Vec3::Y),
+            (Vec3::X, Vec3::NEG_Z, Vec3::Y),
+            (Vec3::NEG_X, Vec3::Z, Vec3::Y),
+            (Vec3::Y, Vec3::X, Vec3::NEG_Z),
+            (Vec3::NEG_Y, Vec3::X, Vec3::Z),
+        ];
+
+        let mut vertices: Vec<Vertex> = Vec::with_capacity(24);
+        let mut indices: Vec<u32> = Vec::with_capacity(36);
+        for (normal, u, v) in faces {
+            let first_index = vertices.len() as u32;
+
+            vertices.push(Vertex::new((normal - u - v) * 0.5, Vec3::ONE, vec2(0.0, 0.0), normal)); // bottom-left
+            vertices.push(Vertex::new((normal + u - v) * 0.5, Vec3::ONE, vec2(1.0, 0.0), normal)); // bottom-right
+            vertices.push(Vertex::new((normal + u + v) * 0.5, Vec3::ONE, vec2(1.0, 1.0), normal)); // top-right
+            vertices.push(Vertex::new((normal - u + v) * 0.5, Vec3::ONE, vec2(0.0, 1.0), normal)); // top-left
+
+            indices.extend_from_slice(&[first_index, first_index + 1, first_index + 2, first_index, first_index + 2, first_index + 3]);
+        }
+
+        return Mesh::new("cube", vertices, indices);
+    }
+
+    pub fn get_name(&self) -> &str {
+        return &self.name;
+    }
+
+    //What the renderer uploads the mesh under. The geometry is part of it, so two different meshes that happen to share a name don't end up drawn with each other's buffers.
+    pub fn get_cache_key(&self) -> String {
+        return format!("{}#{:016x}", self.name, self.content_hash);
+    }
+
+    pub fn get_vertices(&self) -> &Vec<Vertex> {
+        return &self.vertices;
+    }
+
+    pub fn get_indices(&self) -> &Vec<u32> {
+        return &self.indices;
+    }
+
+    pub fn get_bounds(&self) -> BoundingSphere {
+        return BoundingSphere::from_points(self.vertices.iter().map(|vertex| vertex.get_position()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cube_normals_are_unit_length_and_point_outwards() {
+        let cube = Mesh::cube();
+
+        for vertex in cube.get_vertices() {
+            assert!((vertex.get_normal().length() - 1.0).abs() < 1e-6);
+            assert!(vertex.get_normal().dot(vertex.get_position()) > 0.0);
+        }
+    }
+
+    #[test]
+    fn cube_triangles_wind_counter_clockwise_around_their_normal() {
+        let cube = Mesh::cube();
+        let vertices = cube.get_vertices();
+
+        for triangle in cube.get_indices().chunks(3) {
+            let [a, b, c] = [vertices[triangle[0] as usize], vertices[triangle[1] as usize], vertices[triangle[2] as usize]];
+            let face_normal = (b.get_position() - a.get_position()).cross(c.get_position() - a.get_position());
+            assert!(face_normal.dot(a.get_normal()) > 0.0);
+        }
+    }
+
+    #[test]
+    fn cache_keys_only_match_for_the_same_geometry() {
+        let cube = Mesh::cube();
+        let renamed_cube = Mesh::new("box", cube.get_vertices().clone(), cube.get_indices().clone());
+        let impostor = Mesh::new("cube", cube.get_vertices()[..4].to_vec(), vec![0, 1, 2]);
+
+        assert_eq!(cube.get_cache_key(), Mesh::cube().get_cache_key());
+        assert_ne!(cube.get_cache_key(), renamed_cube.get_cache_key());
+        assert_ne!(cube.get_cache_key(), impostor.get_cache_key());
+    }
+}
\ No newline at end of file
@@ -1,2 +1,10 @@
 pub mod transform;
-pub mod model;
\ No newline at end of file
+pub mod mesh;
+pub mod bounding_sphere;
+pub mod frustum;
+pub mod light;
+pub mod material;
+pub mod skybox;
+pub mod text;
+pub mod post_process;
+pub mod shadow;
\ No newline at end of file
@@ -0,0 +1,135 @@
+use glam::{vec4, Vec4};
+
+//One full screen pass run on the HDR scene before it is presented.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PostProcessEffect {
+    //Makes everything brighter than the threshold glow, best placed before tonemapping while colors are still HDR. Intensity scales the glow added back onto the scene.
+    Bloom { threshold: f32, intensity: f32 },
+    //Maps HDR colors into the displayable range with the ACES filmic curve, after scaling them by the exposure.
+    Tonemap { exposure: f32 },
+    //Brightens or darkens the mid tones, 1 leaves the image as it is. The sRGB encoding of the output is applied on top regardless.
+    Gamma { gamma: f32 },
+    //Darkens the corners, starting at the radius from the center where 1 reaches the middle of the edges.
+    Vignette { intensity: f32, radius: f32 },
+    //Fast approximate anti-aliasing, best placed after tonemapping.
+    Fxaa,
+    //Remaps colors through a lookup table, a strip of square slices like the usual 256x16 LUT images. Red grows to the right within a slice, green downwards and blue from slice to slice.
+    ColorGrading { lut_path: String },
+}
+
+impl PostProcessEffect {
+    pub fn get_pipeline_name(&self) -> &'static str {
+        return match self {
+            PostProcessEffect::Bloom { .. } => "post_bloom_composite",
+            PostProcessEffect::Tonemap { .. } => "post_tonemap",
+            PostProcessEffect::Gamma { .. } => "post_gamma",
+            PostProcessEffect::Vignette { .. } => "post_vignette",
+            PostProcessEffect::Fxaa => "post_fxaa",
+            PostProcessEffect::ColorGrading { .. } => "post_color_grading",
+        };
+    }
+
+    //What the shader of the effect reads from its uniforms.
+    pub fn get_parameters(&self) -> Vec4 {
+        return match self {
+            PostProcessEffect::Bloom { threshold, intensity } => vec4(*threshold, *intensity, 0.0, 0.0),
+            PostProcessEffect::Tonemap { exposure } => vec4(*exposure, 0.0, 0.0, 0.0),
+            PostProcessEffect::Gamma { gamma } => vec4(*gamma, 0.0, 0.0, 0.0),
+            PostProcessEffect::Vignette { intensity, radius } => vec4(*intensity, *radius, 0.0, 0.0),
+            PostProcessEffect::Fxaa => Vec4::ZERO,
+            PostProcessEffect::ColorGrading { .. } => Vec4::ZERO,
+        };
+    }
+
+    pub fn get_lut_path(&self) -> Option<&str> {
+        return match self {
+            PostProcessEffect::ColorGrading { lut_path } => Some(lut_path),
+            _ => None,
+        };
+    }
+}
+
+//The effects run on the scene, in order. Whatever is left at the end gets written to the presented image.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PostProcessChain {
+    effects: Vec<PostProcessEffect>,
+}
+
+impl PostProcessChain {
+    //No effects at all, HDR colors are clamped when presented.
+    pub fn empty() -> Self {
+        return PostProcessChain { effects: vec![] };
+    }
+
+    pub fn with(mut self, effect: PostProcessEffect) -> Self {
+        self.effects.push(effect);
+        return self;
+    }
+
+    //Indices past the end add the effect at the end.
+    pub fn insert(&mut self, index: usize, effect: PostProcessEffect) {
+        self.effects.insert(index.min(self.effects.len()), effect);
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<PostProcessEffect> {
+        if index >= self.effects.len() {
+            return None;
+        }
+        return Some(self.effects.remove(index));
+    }
+
+    //Moves an effect so it ends up at the new index, the effects in between shift over by one.
+    pub fn move_effect(&mut self, from: usize, to: usize) {
+        if from >= self.effects.len() {
+            return;
+        }
+
+        let effect = self.effects.remove(from);
+        self.effects.insert(to.min(self.effects.len()), effect);
+    }
+
+    pub fn get_effects(&self) -> &[PostProcessEffect] {
+        return &self.effects;
+    }
+}
+
+//Bloom on anything brighter than white, then tonemapped into the displayable range.
+impl Default for PostProcessChain {
+    fn default() -> Self {
+        return PostProcessChain::empty().with(PostProcessEffect::Bloom { threshold: 1.0, intensity: 0.05 }).with(PostProcessEffect::Tonemap { exposure: 1.0 });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(chain: &PostProcessChain) -> Vec<&'static str> {
+        return chain.get_effects().iter().map(|effect| effect.get_pipeline_name()).collect();
+    }
+
+    #[test]
+    fn effects_can_be_added_removed_and_reordered() {
+        let mut chain = PostProcessChain::empty().with(PostProcessEffect::Tonemap { exposure: 1.0 }).with(PostProcessEffect::Fxaa);
+        chain.insert(1, PostProcessEffect::Vignette { intensity: 0.5, radius: 0.8 });
+        chain.insert(10, PostProcessEffect::Gamma { gamma: 1.1 });
+        assert_eq!(names(&chain), vec!["post_tonemap", "post_vignette", "post_fxaa", "post_gamma"]);
+
+        chain.move_effect(3, 0);
+        assert_eq!(names(&chain), vec!["post_gamma", "post_tonemap", "post_vignette", "post_fxaa"]);
+        chain.move_effect(0, 10);
+        assert_eq!(names(&chain), vec!["post_tonemap", "post_vignette", "post_fxaa", "post_gamma"]);
+
+        assert_eq!(chain.remove(1), Some(PostProcessEffect::Vignette { intensity: 0.5, radius: 0.8 }));
+        assert_eq!(chain.remove(5), None);
+        assert_eq!(names(&chain), vec!["post_tonemap", "post_fxaa", "post_gamma"]);
+    }
+
+    #[test]
+    fn only_color_grading_needs_a_lut() {
+        let grading = PostProcessEffect::ColorGrading { lut_path: "lut.png".to_string() };
+
+        assert_eq!(grading.get_lut_path(), Some("lut.png"));
+        assert!(PostProcessChain::default().get_effects().iter().all(|effect| effect.get_lut_path().is_none()));
+    }
+}
\ No newline at end of file
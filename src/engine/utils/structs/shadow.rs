@@ -0,0 +1,132 @@
+use glam::{vec3, Mat4, Vec3};
+
+//Has to match MAX_SHADOW_CASCADES in shader.frag.
+pub const MAX_SHADOW_CASCADES: usize = 4;
+
+//How the directional light casts shadows. The camera frustum up to the shadow distance is split into cascades, each with its own square shadow map,
+//so close shadows stay sharp while far away ones still show up.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShadowSettings {
+    //Width and height in texels of the shadow map of every cascade.
+    pub resolution: u32,
+    //How far surfaces are pushed along their normal before being looked up, in world units. Too little and surfaces shadow themselves, too much and shadows come loose from their casters.
+    pub bias: f32,
+    pub cascades: usize,
+    //Nothing further away from the camera than this is shadowed.
+    pub distance: f32,
+}
+
+impl ShadowSettings {
+    //Cascades are clamped between 1 and MAX_SHADOW_CASCADES.
+    pub fn new(resolution: u32, bias: f32, cascades: usize, distance: f32) -> Self {
+        return ShadowSettings { resolution: resolution.max(1), bias, cascades: cascades.clamp(1, MAX_SHADOW_CASCADES), distance };
+    }
+}
+
+impl Default for ShadowSettings {
+    fn default() -> Self {
+        return ShadowSettings::new(2048, 0.05, 3, 60.0);
+    }
+}
+
+//World space corners of the camera frustum, the near four first. The projection comes from perspective_rh_gl so clip space depth runs from -1 to 1.
+pub fn frustum_corners(view_projection: Mat4) -> [Vec3; 8] {
+    let inverse = view_projection.inverse();
+    return [-1.0, 1.0].map(|depth| [vec3(-1.0, -1.0, depth), vec3(1.0, -1.0, depth), vec3(1.0, 1.0, depth), vec3(-1.0, 1.0, depth)].map(|corner| inverse.project_point3(corner))).concat().try_into().unwrap();
+}
+
+//Far distances of the cascades, between an even and a logarithmic split so the close cascades cover less and stay sharper.
+pub fn cascade_splits(near: f32, distance: f32, cascades: usize) -> Vec<f32> {
+    const LOGARITHMIC_WEIGHT: f32 = 0.75;
+
+    return (1..=cascades).map(|cascade| {
+        let fraction = cascade as f32 / cascades as f32;
+        let logarithmic = near * (distance / near).powf(fraction);
+        let even = near + (distance - near) * fraction;
+        return LOGARITHMIC_WEIGHT * logarithmic + (1.0 - LOGARITHMIC_WEIGHT) * even;
+    }).collect();
+}
+
+//Light view projections of every cascade, orthographic boxes around the slices of the camera frustum they cover. Near and far are the distances the corners sit at.
+//The boxes are fit around bounding spheres and snapped to whole texels, so shadows neither change size nor shimmer when the camera turns or moves.
+//They reach the shadow distance further towards the light, so casters outside of the camera frustum still throw their shadows into it.
+pub fn cascade_view_projections(frustum_corners: &[Vec3; 8], near: f32, far: f32, light_direction: Vec3, settings: &ShadowSettings) -> Vec<Mat4> {
+    let up = if light_direction.cross(Vec3::Y).length_squared() < 1e-6 { Vec3::Z } else { Vec3::Y };
+    let light_view = Mat4::look_to_rh(Vec3::ZERO, light_direction, up);
+    let corner_at = |corner: usize, depth: f32| frustum_corners[corner].lerp(frustum_corners[corner + 4], (depth - near) / (far - near));
+
+    let mut slice_near = near;
+    return cascade_splits(near, settings.distance.min(far), settings.cascades).into_iter().map(|slice_far| {
+        let corners: Vec<Vec3> = (0..4).flat_map(|corner| [corner_at(corner, slice_near), corner_at(corner, slice_far)]).collect();
+        slice_near = slice_far;
+
+        let center = corners.iter().sum::<Vec3>() / corners.len() as f32;
+        //Padded by a couple of texels so the slice still fits once snapped, and rounded up so floating point noise doesn't make the box wobble in size.
+        let radius = corners.iter().map(|corner| corner.distance(center)).fold(0.0, f32::max) * (1.0 + 2.0 / settings.resolution as f32);
+        let radius = (radius * 16.0).ceil() / 16.0;
+
+        let texel = 2.0 * radius / settings.resolution as f32;
+        let light_center = light_view.transform_point3(center);
+        let snapped = (light_center.truncate() / texel).round() * texel;
+        let projection = Mat4::orthographic_rh(snapped.x - radius, snapped.x + radius, snapped.y - radius, snapped.y + radius, -light_center.z - radius - settings.distance, -light_center.z + radius);
+
+        return projection * light_view;
+    }).collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn camera_corners(camera_position: Vec3) -> [Vec3; 8] {
+        let view = Mat4::look_to_rh(camera_position, vec3(0.0, 0.0, -1.0), Vec3::Y);
+        return frustum_corners(Mat4::perspective_rh_gl(45.0_f32.to_radians(), 4.0 / 3.0, 0.1, 1000.0) * view);
+    }
+
+    #[test]
+    fn splits_grow_until_the_shadow_distance() {
+        let splits = cascade_splits(0.1, 60.0, 4);
+
+        assert_eq!(splits.len(), 4);
+        assert!(splits.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!((splits[3] - 60.0).abs() < 1e-3);
+        assert!(splits[0] < 60.0 / 4.0);
+    }
+
+    #[test]
+    fn every_slice_fits_inside_its_cascade() {
+        let settings = ShadowSettings::new(1024, 0.05, 3, 50.0);
+        let corners = camera_corners(vec3(1.0, 2.0, 3.0));
+        let matrices = cascade_view_projections(&corners, 0.1, 1000.0, vec3(-0.4, -1.0, -0.6).normalize(), &settings);
+
+        let mut slice_near = 0.1;
+        for (matrix, slice_far) in matrices.iter().zip(cascade_splits(0.1, 50.0, 3)) {
+            for corner in 0..4 {
+                for depth in [slice_near, slice_far] {
+                    let point = corners[corner].lerp(corners[corner + 4], (depth - 0.1) / (1000.0 - 0.1));
+                    let clip = matrix.project_point3(point);
+                    assert!(clip.x.abs() <= 1.0 && clip.y.abs() <= 1.0 && (0.0..=1.0).contains(&clip.z), "{:?} is outside of its cascade", clip);
+                }
+            }
+            slice_near = slice_far;
+        }
+    }
+
+    //The box only ever moves by whole texels, otherwise shadow edges crawl while the camera moves.
+    #[test]
+    fn cascades_snap_to_whole_texels() {
+        let settings = ShadowSettings::new(1024, 0.05, 1, 20.0);
+        for camera_x in [0.0, 0.013, 0.37, 5.1] {
+            let matrix = cascade_view_projections(&camera_corners(vec3(camera_x, 0.0, 0.0)), 0.1, 1000.0, vec3(0.0, -1.0, 0.0), &settings)[0];
+            let offset_in_texels = matrix.w_axis.x * settings.resolution as f32 / 2.0;
+            assert!((offset_in_texels - offset_in_texels.round()).abs() < 1e-2, "{} is not a whole amount of texels", offset_in_texels);
+        }
+    }
+
+    #[test]
+    fn cascades_are_clamped() {
+        assert_eq!(ShadowSettings::new(0, 0.0, 9, 10.0).cascades, MAX_SHADOW_CASCADES);
+        assert_eq!(ShadowSettings::new(0, 0.0, 0, 10.0).cascades, 1);
+        assert_eq!(ShadowSettings::new(0, 0.0, 0, 10.0).resolution, 1);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,195 @@
+use std::{error::Error, f32::consts::PI};
+
+use glam::{vec2, vec3, Vec2, Vec3, Vec4};
+use image::DynamicImage;
+
+//Where a skybox is loaded from. Faces are ordered +X, -X, +Y, -Y, +Z, -Z, the same order Vulkan uses for the layers of a cubemap.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SkyboxSource {
+    Faces([String; 6]),
+    Equirectangular(String),
+}
+
+//A cubemap decoded on the CPU, six square faces of linear RGBA texels stored one after the other.
+pub struct CubemapPixels {
+    size: u32,
+    texels: Vec<Vec4>,
+}
+
+impl CubemapPixels {
+    pub fn load(source: &SkyboxSource) -> Result<Self, Box<dyn Error>> {
+        return match source {
+            SkyboxSource::Faces(paths) => CubemapPixels::from_faces(paths),
+            SkyboxSource::Equirectangular(path) => CubemapPixels::from_equirectangular(path),
+        };
+    }
+
+    //Every face of the cubemap is the same color, used when there is no skybox.
+    pub fn solid(color: Vec4) -> Self {
+        return CubemapPixels { size: 1, texels: vec![color; 6] };
+    }
+
+    fn from_faces(paths: &[String; 6]) -> Result<Self, Box<dyn Error>> {
+        let mut size = 0;
+        let mut texels = vec![];
+
+        for path in paths.iter() {
+            let face = image::open(path)?;
+            if face.width() != face.height() || (size != 0 && face.width() != size) {
+                return Err(format!("Skybox face {} is {}x{}, all faces have to be square and the same size", path, face.width(), face.height()).into());
+            }
+
+            size = face.width();
+            texels.extend(to_linear_texels(&face));
+        }
+
+        return Ok(CubemapPixels { size, texels });
+    }
+
+    //Every face texel looks up the panorama pixel its direction points at, a quarter of the panorama width per face keeps roughly the same detail.
+    fn from_equirectangular(path: &str) -> Result<Self, Box<dyn Error>> {
+        let panorama = image::open(path)?;
+        let (width, height) = (panorama.width(), panorama.height());
+        let panorama_texels = to_linear_texels(&panorama);
+        let size = (width / 4).max(1);
+
+        let mut texels = Vec::with_capacity((size * size * 6) as usize);
+        for face in 0..6 {
+            for y in 0..size {
+                for x in 0..size {
+                    let direction = face_direction(face, (x as f32 + 0.5) / size as f32, (y as f32 + 0.5) / size as f32);
+                    let uv = equirectangular_uv(direction);
+                    let pixel_x = ((uv.x * width as f32) as u32).min(width - 1);
+                    let pixel_y = ((uv.y * height as f32) as u32).min(height - 1);
+                    texels.push(panorama_texels[(pixel_y * width + pixel_x) as usize]);
+                }
+            }
+        }
+
+        return Ok(CubemapPixels { size, texels });
+    }
+
+    pub fn get_size(&self) -> u32 {
+        return self.size;
+    }
+
+    pub fn get_texels(&self) -> &[Vec4] {
+        return &self.texels;
+    }
+}
+
+//HDR images are already linear, everything else is treated as sRGB and converted.
+fn to_linear_texels(image: &DynamicImage) -> Vec<Vec4> {
+    let is_linear = matches!(image, DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_));
+
+    return image.to_rgba32f().pixels().map(|pixel| {
+        let [r, g, b, a] = pixel.0;
+        if is_linear {
+            return Vec4::new(r, g, b, a);
+        }
+        return Vec4::new(srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b), a);
+    }).collect();
+}
+
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        return value / 12.92;
+    }
+    return ((value + 0.055) / 1.055).powf(2.4);
+}
+
+//World space direction through a texel of a cubemap face, s and t go from 0 to 1 across the face. Inverse of the face selection Vulkan does when sampling.
+pub fn face_direction(face: usize, s: f32, t: f32) -> Vec3 {
+    let a = s * 2.0 - 1.0;
+    let b = t * 2.0 - 1.0;
+
+    let direction = match face {
+        0 => vec3(1.0, -b, -a),
+        1 => vec3(-1.0, -b, a),
+        2 => vec3(a, 1.0, b),
+        3 => vec3(a, -1.0, -b),
+        4 => vec3(a, -b, 1.0),
+        _ => vec3(-a, -b, -1.0),
+    };
+
+    return direction.normalize();
+}
+
+//Where a direction lands on an equirectangular panorama. The middle of the image looks down -Z and the top row is straight up.
+pub fn equirectangular_uv(direction: Vec3) -> Vec2 {
+    let direction = direction.normalize();
+    let u = 0.5 + direction.x.atan2(-direction.z) / (2.0 * PI);
+    let v = direction.y.clamp(-1.0, 1.0).acos() / PI;
+
+    return vec2(u, v);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 1e-5;
+
+    #[test]
+    fn face_centers_point_along_their_axis() {
+        let axes = [Vec3::X, Vec3::NEG_X, Vec3::Y, Vec3::NEG_Y, Vec3::Z, Vec3::NEG_Z];
+
+        for (face, axis) in axes.iter().enumerate() {
+            assert!(face_direction(face, 0.5, 0.5).abs_diff_eq(*axis, EPSILON), "face {} does not point along {:?}", face, axis);
+        }
+    }
+
+    //Picks the face the same way Vulkan does, the major axis of the direction, and recovers the texel coordinates from it.
+    #[test]
+    fn face_direction_round_trips_through_vulkan_face_selection() {
+        for face in 0..6 {
+            for (s, t) in [(0.1, 0.2), (0.8, 0.3), (0.4, 0.9)] {
+                let direction = face_direction(face, s, t);
+                let (selected_face, sc, tc, ma) = if direction.x.abs() >= direction.y.abs() && direction.x.abs() >= direction.z.abs() {
+                    if direction.x > 0.0 { (0, -direction.z, -direction.y, direction.x) } else { (1, direction.z, -direction.y, -direction.x) }
+                } else if direction.y.abs() >= direction.z.abs() {
+                    if direction.y > 0.0 { (2, direction.x, direction.z, direction.y) } else { (3, direction.x, -direction.z, -direction.y) }
+                } else if direction.z > 0.0 {
+                    (4, direction.x, -direction.y, direction.z)
+                } else {
+                    (5, -direction.x, -direction.y, -direction.z)
+                };
+
+                assert_eq!(selected_face, face);
+                assert!(((sc / ma + 1.0) * 0.5 - s).abs() < EPSILON && ((tc / ma + 1.0) * 0.5 - t).abs() < EPSILON, "face {} texel ({}, {}) does not round trip", face, s, t);
+            }
+        }
+    }
+
+    #[test]
+    fn faces_are_loaded_in_order_and_linearized() {
+        //One directory per process, so test runs happening at the same time don't overwrite each other's faces.
+        let directory = std::env::temp_dir().join(format!("skybox_faces_test_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+
+        let paths: Vec<String> = (0..6).map(|face| {
+            let path = directory.join(format!("face_{}.png", face));
+            image::RgbaImage::from_pixel(2, 2, image::Rgba([face as u8 * 40, 255, 0, 255])).save(&path).unwrap();
+            path.to_string_lossy().to_string()
+        }).collect();
+
+        let cubemap = CubemapPixels::load(&SkyboxSource::Faces(paths.clone().try_into().unwrap())).unwrap();
+        assert_eq!(cubemap.get_size(), 2);
+        assert_eq!(cubemap.get_texels().len(), 6 * 4);
+        assert!((cubemap.get_texels()[5 * 4].x - srgb_to_linear(200.0 / 255.0)).abs() < EPSILON);
+        assert_eq!(cubemap.get_texels()[0].y, 1.0);
+
+        image::RgbaImage::new(4, 2).save(&paths[3]).unwrap();
+        assert!(CubemapPixels::load(&SkyboxSource::Faces(paths.try_into().unwrap())).is_err());
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn equirectangular_uv_maps_poles_and_horizon() {
+        assert!((equirectangular_uv(Vec3::Y).y).abs() < EPSILON);
+        assert!((equirectangular_uv(Vec3::NEG_Y).y - 1.0).abs() < EPSILON);
+        assert!(equirectangular_uv(Vec3::NEG_Z).abs_diff_eq(vec2(0.5, 0.5), EPSILON));
+        assert!(equirectangular_uv(Vec3::X).abs_diff_eq(vec2(0.75, 0.5), EPSILON));
+    }
+}
\ No newline at end of file
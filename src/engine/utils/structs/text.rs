@@ -0,0 +1,22 @@
+use glam::{Vec2, Vec4};
+
+//A piece of text drawn on top of the frame. The position is the top left corner in pixels from the top left of the viewport.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextSection {
+    pub text: String,
+    pub position: Vec2,
+    pub color: Vec4,
+    //Multiplies the size the font atlas was baked at.
+    pub scale: f32,
+}
+
+impl TextSection {
+    pub fn new(text: &str, position: Vec2) -> Self {
+        return TextSection {
+            text: text.to_string(),
+            position,
+            color: Vec4::ONE,
+            scale: 1.0,
+        };
+    }
+}
\ No newline at end of file
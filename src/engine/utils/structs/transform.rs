@@ -1,52 +1,152 @@
-use glam::Vec3;
+use glam::{EulerRot, Mat4, Quat, Vec3};
 
 #[derive(Clone, Debug)]
 pub struct Transform {
     pub position: Vec3,
-    pub rotation: Vec3,
-    //pub size: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
 }
 
 impl Transform {
-    pub fn new(position: Vec3, rotation: Vec3, /* size: Vec3 */) -> Self {
-        return Transform { position, rotation, /* size */ }
+    pub fn new(position: Vec3, rotation: Quat, scale: Vec3) -> Self {
+        return Transform { position, rotation, scale }
+    }
+
+    //Euler angles are in radians and applied in X * Y * Z order, the same order the camera has always used.
+    pub fn from_euler(position: Vec3, euler: Vec3, scale: Vec3) -> Self {
+        return Transform::new(position, Quat::from_euler(EulerRot::XYZ, euler.x, euler.y, euler.z), scale);
     }
 
     pub fn get_position(&self) -> Vec3 {
         return self.position;
     }
 
-    pub fn get_rotation(&self) -> Vec3 {
+    pub fn get_rotation(&self) -> Quat {
         return self.rotation;
     }
 
-    /*
-    pub fn get_size(&self) -> Vec3 {
-        return self.size;
+    pub fn get_euler(&self) -> Vec3 {
+        let (x, y, z) = self.rotation.to_euler(EulerRot::XYZ);
+        return Vec3::new(x, y, z);
+    }
+
+    pub fn set_euler(&mut self, euler: Vec3) {
+        self.rotation = Quat::from_euler(EulerRot::XYZ, euler.x, euler.y, euler.z);
+    }
+
+    //Model matrix, scale first, then rotation, then translation.
+    pub fn to_matrix(&self) -> Mat4 {
+        return Mat4::from_scale_rotation_translation(self.scale, self.rotation, self.position);
+    }
+
+    //A camera transform is stored the way the view matrix uses it, so the rotation and position are the inverse of the camera's placement in the world.
+    pub fn to_view_matrix(&self) -> Mat4 {
+        return Mat4::from_quat(self.rotation) * Mat4::from_translation(self.position);
+    }
+
+    //Where a camera transform actually sits in the world, the view matrix moves this point to the origin.
+    pub fn get_camera_position(&self) -> Vec3 {
+        return -self.position;
     }
-    */
 
+    //The basis vectors follow the view matrix convention above, they are the world space directions that end up as +X, +Y and -Z in view space.
     pub fn right(&self) -> Vec3 {
-        return Vec3::new(
-            self.rotation.y.cos() * self.rotation.z.cos(),
-            self.rotation.z.sin(),
-            self.rotation.y.sin() * self.rotation.z.cos(),
-        );
+        return self.rotation.inverse() * Vec3::X;
     }
 
     pub fn forward(&self) -> Vec3 {
-        return Vec3::new(
-            self.rotation.y.sin(),
-            -self.rotation.x.sin(),
-            -self.rotation.y.cos(),
-        );
+        return self.rotation.inverse() * Vec3::NEG_Z;
     }
 
     pub fn up(&self) -> Vec3 {
-        return Vec3::new(
-            self.rotation.x.sin() * self.rotation.y.sin() * self.rotation.z.cos() - self.rotation.x.cos() * self.rotation.z.sin(),
-            self.rotation.x.cos() * self.rotation.z.cos() + self.rotation.x.sin() * self.rotation.y.sin() * self.rotation.z.sin(),
-            -self.rotation.x.sin() * self.rotation.y.cos(),
-        );
+        return self.rotation.inverse() * Vec3::Y;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{vec3, Mat4, Vec3};
+
+    use super::Transform;
+
+    const EPSILON: f32 = 1e-5;
+
+    fn test_rotations() -> Vec<Vec3> {
+        return vec![
+            vec3(0.0, 0.0, 0.0),
+            vec3(0.3, 0.0, 0.0),
+            vec3(0.0, 2.5, 0.0),
+            vec3(0.0, 0.0, 0.8),
+            vec3(-1.2, 0.7, 0.4),
+            vec3(1.4, -3.0, -1.1),
+        ];
+    }
+
+    #[test]
+    fn basis_vectors_are_orthonormal() {
+        for euler in test_rotations() {
+            let transform = Transform::from_euler(Vec3::ZERO, euler, Vec3::ONE);
+            let (right, up, forward) = (transform.right(), transform.up(), transform.forward());
+
+            assert!((right.length() - 1.0).abs() < EPSILON, "right is not unit length for {:?}", euler);
+            assert!((up.length() - 1.0).abs() < EPSILON, "up is not unit length for {:?}", euler);
+            assert!((forward.length() - 1.0).abs() < EPSILON, "forward is not unit length for {:?}", euler);
+
+            assert!(right.dot(up).abs() < EPSILON, "right and up are not orthogonal for {:?}", euler);
+            assert!(right.dot(forward).abs() < EPSILON, "right and forward are not orthogonal for {:?}", euler);
+            assert!(up.dot(forward).abs() < EPSILON, "up and forward are not orthogonal for {:?}", euler);
+
+            //Right handed, right x up points backwards.
+            assert!(right.cross(up).abs_diff_eq(-forward, EPSILON), "basis is not right handed for {:?}", euler);
+        }
+    }
+
+    #[test]
+    fn basis_vectors_agree_with_view_matrix() {
+        for euler in test_rotations() {
+            let transform = Transform::from_euler(vec3(1.0, -2.0, 3.0), euler, Vec3::ONE);
+            let view = transform.to_view_matrix();
+
+            assert!(view.transform_vector3(transform.right()).abs_diff_eq(Vec3::X, EPSILON), "right does not map to +X for {:?}", euler);
+            assert!(view.transform_vector3(transform.up()).abs_diff_eq(Vec3::Y, EPSILON), "up does not map to +Y for {:?}", euler);
+            assert!(view.transform_vector3(transform.forward()).abs_diff_eq(Vec3::NEG_Z, EPSILON), "forward does not map to -Z for {:?}", euler);
+        }
+    }
+
+    #[test]
+    fn camera_position_maps_to_view_origin() {
+        for euler in test_rotations() {
+            let transform = Transform::from_euler(vec3(1.0, -2.0, 3.0), euler, Vec3::ONE);
+
+            assert!(transform.to_view_matrix().transform_point3(transform.get_camera_position()).abs_diff_eq(Vec3::ZERO, EPSILON), "camera position is not the view origin for {:?}", euler);
+        }
+    }
+
+    #[test]
+    fn view_matrix_matches_euler_rotation_order() {
+        for euler in test_rotations() {
+            let transform = Transform::from_euler(vec3(0.5, 1.0, -4.0), euler, Vec3::ONE);
+            let expected = Mat4::from_rotation_x(euler.x) * Mat4::from_rotation_y(euler.y) * Mat4::from_rotation_z(euler.z) * Mat4::from_translation(vec3(0.5, 1.0, -4.0));
+
+            assert!(transform.to_view_matrix().abs_diff_eq(expected, EPSILON), "view matrix does not match X * Y * Z for {:?}", euler);
+        }
+    }
+
+    #[test]
+    fn model_matrix_applies_scale_rotation_then_translation() {
+        let transform = Transform::from_euler(vec3(1.0, 2.0, 3.0), vec3(0.0, std::f32::consts::FRAC_PI_2, 0.0), vec3(2.0, 1.0, 1.0));
+
+        //+X is scaled to length 2, then turned a quarter around Y onto -Z, then moved.
+        let point = transform.to_matrix().transform_point3(Vec3::X);
+        assert!(point.abs_diff_eq(vec3(1.0, 2.0, 1.0), EPSILON), "unexpected point {:?}", point);
+    }
+
+    #[test]
+    fn euler_round_trips_for_camera_angles() {
+        let mut transform = Transform::from_euler(Vec3::ZERO, Vec3::ZERO, Vec3::ONE);
+        let euler = vec3(-1.2, 0.9, 0.0);
+        transform.set_euler(euler);
+
+        assert!(transform.get_euler().abs_diff_eq(euler, EPSILON));
     }
 }
\ No newline at end of file
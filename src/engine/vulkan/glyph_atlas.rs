@@ -0,0 +1,207 @@
+use std::{collections::HashMap, error::Error};
+
+use fontdue::{Font, FontSettings};
+use glam::{vec2, Vec2};
+
+//Printable ASCII, anything else is drawn as the fallback glyph.
+const FIRST_CHARACTER: char = ' ';
+const LAST_CHARACTER: char = '~';
+const FALLBACK_CHARACTER: char = '?';
+
+const ATLAS_WIDTH: u32 = 512;
+//Empty texels around every glyph so linear filtering never bleeds a neighbour in.
+const GLYPH_PADDING: u32 = 1;
+
+#[derive(Clone, Copy, Debug)]
+struct Glyph {
+    atlas_position: [u32; 2],
+    size: [u32; 2],
+    //From the pen position on the baseline to the top left corner of the bitmap, y points down.
+    offset: Vec2,
+    advance: f32,
+}
+
+//Where one glyph ends up on screen in pixels and which part of the atlas it shows.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GlyphQuad {
+    pub min: Vec2,
+    pub max: Vec2,
+    pub uv_min: Vec2,
+    pub uv_max: Vec2,
+}
+
+//Every glyph of a font rasterized once at a fixed pixel size and packed into a single coverage texture, row by row.
+pub struct GlyphAtlas {
+    width: u32,
+    height: u32,
+    coverage: Vec<u8>,
+    glyphs: HashMap<char, Glyph>,
+    ascent: f32,
+    line_height: f32,
+}
+
+impl GlyphAtlas {
+    pub fn from_font_file(path: &str, pixel_size: f32) -> Result<Self, Box<dyn Error>> {
+        let font_data = std::fs::read(path)?;
+        return GlyphAtlas::from_font_bytes(&font_data, pixel_size);
+    }
+
+    pub fn from_font_bytes(font_data: &[u8], pixel_size: f32) -> Result<Self, Box<dyn Error>> {
+        let font = Font::from_bytes(font_data, FontSettings { scale: pixel_size, ..Default::default() })?;
+        let line_metrics = font.horizontal_line_metrics(pixel_size).ok_or("Font has no horizontal line metrics")?;
+
+        let mut bitmaps = vec![];
+        let mut glyphs = HashMap::new();
+        let mut pen = [GLYPH_PADDING, GLYPH_PADDING];
+        let mut row_height = 0;
+
+        for character in FIRST_CHARACTER..=LAST_CHARACTER {
+            let (metrics, bitmap) = font.rasterize(character, pixel_size);
+            let size = [metrics.width as u32, metrics.height as u32];
+
+            if pen[0] + size[0] + GLYPH_PADDING > ATLAS_WIDTH {
+                pen = [GLYPH_PADDING, pen[1] + row_height + GLYPH_PADDING];
+                row_height = 0;
+            }
+
+            glyphs.insert(character, Glyph {
+                atlas_position: pen,
+                size,
+                offset: vec2(metrics.xmin as f32, -(metrics.ymin as f32 + metrics.height as f32)),
+                advance: metrics.advance_width,
+            });
+            bitmaps.push((pen, size, bitmap));
+
+            pen[0] += size[0] + GLYPH_PADDING;
+            row_height = row_height.max(size[1]);
+        }
+
+        let height = pen[1] + row_height + GLYPH_PADDING;
+        let mut coverage = vec![0; (ATLAS_WIDTH * height) as usize];
+        for (position, size, bitmap) in bitmaps.iter() {
+            for row in 0..size[1] {
+                let source = (row * size[0]) as usize;
+                let destination = ((position[1] + row) * ATLAS_WIDTH + position[0]) as usize;
+                coverage[destination..destination + size[0] as usize].copy_from_slice(&bitmap[source..source + size[0] as usize]);
+            }
+        }
+
+        return Ok(GlyphAtlas {
+            width: ATLAS_WIDTH,
+            height,
+            coverage,
+            glyphs,
+            ascent: line_metrics.ascent,
+            line_height: line_metrics.new_line_size,
+        });
+    }
+
+    pub fn get_width(&self) -> u32 {
+        return self.width;
+    }
+
+    pub fn get_height(&self) -> u32 {
+        return self.height;
+    }
+
+    //White texels with the coverage in alpha, so the atlas can be uploaded like any other RGBA texture.
+    pub fn to_rgba(&self) -> Vec<u8> {
+        return self.coverage.iter().flat_map(|coverage| [255, 255, 255, *coverage]).collect();
+    }
+
+    //Quads for every visible glyph of the text, with the top left corner of the first line at the position. Newlines start a new line.
+    pub fn layout(&self, text: &str, position: Vec2, scale: f32) -> Vec<GlyphQuad> {
+        let mut quads = vec![];
+        let mut pen = vec2(position.x, position.y + self.ascent * scale);
+
+        for character in text.chars() {
+            if character == '\n' {
+                pen = vec2(position.x, pen.y + self.line_height * scale);
+                continue;
+            }
+
+            let Some(glyph) = self.glyphs.get(&character).or(self.glyphs.get(&FALLBACK_CHARACTER)) else {
+                continue;
+            };
+
+            if glyph.size[0] > 0 && glyph.size[1] > 0 {
+                let min = pen + glyph.offset * scale;
+                let atlas_size = vec2(self.width as f32, self.height as f32);
+                let uv_min = vec2(glyph.atlas_position[0] as f32, glyph.atlas_position[1] as f32) / atlas_size;
+
+                quads.push(GlyphQuad {
+                    min,
+                    max: min + vec2(glyph.size[0] as f32, glyph.size[1] as f32) * scale,
+                    uv_min,
+                    uv_max: uv_min + vec2(glyph.size[0] as f32, glyph.size[1] as f32) / atlas_size,
+                });
+            }
+
+            pen.x += glyph.advance * scale;
+        }
+
+        return quads;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FONT_PATH: &str = "src/engine/vulkan/base_resources/DejaVuSansMono.ttf";
+    const EPSILON: f32 = 1e-4;
+
+    fn load_atlas() -> GlyphAtlas {
+        return GlyphAtlas::from_font_file(FONT_PATH, 20.0).unwrap();
+    }
+
+    #[test]
+    fn every_printable_character_is_packed_inside_the_atlas_without_overlapping() {
+        let atlas = load_atlas();
+
+        assert_eq!(atlas.glyphs.len(), (FIRST_CHARACTER..=LAST_CHARACTER).count());
+        let glyphs: Vec<&Glyph> = atlas.glyphs.values().collect();
+        for (index, glyph) in glyphs.iter().enumerate() {
+            assert!(glyph.atlas_position[0] + glyph.size[0] <= atlas.get_width() && glyph.atlas_position[1] + glyph.size[1] <= atlas.get_height());
+
+            for other in glyphs[index + 1..].iter() {
+                let overlaps_x = glyph.atlas_position[0] < other.atlas_position[0] + other.size[0] && other.atlas_position[0] < glyph.atlas_position[0] + glyph.size[0];
+                let overlaps_y = glyph.atlas_position[1] < other.atlas_position[1] + other.size[1] && other.atlas_position[1] < glyph.atlas_position[1] + glyph.size[1];
+                assert!(!(overlaps_x && overlaps_y));
+            }
+        }
+        assert_eq!(atlas.to_rgba().len(), (atlas.get_width() * atlas.get_height() * 4) as usize);
+    }
+
+    #[test]
+    fn layout_advances_along_the_line_and_wraps_on_newlines() {
+        let atlas = load_atlas();
+        let quads = atlas.layout("AA A\nA", vec2(10.0, 20.0), 1.0);
+
+        //The space has no bitmap so it gets no quad, but it still moves the pen.
+        assert_eq!(quads.len(), 4);
+        let advance = atlas.glyphs[&'A'].advance;
+        assert!((quads[1].min.x - quads[0].min.x - advance).abs() < EPSILON);
+        assert!((quads[2].min.x - quads[1].min.x - 2.0 * advance).abs() < EPSILON);
+        assert!((quads[3].min.y - quads[0].min.y - atlas.line_height).abs() < EPSILON);
+        assert!((quads[3].min.x - quads[0].min.x).abs() < EPSILON);
+        assert!(quads.iter().all(|quad| quad.min.y >= 20.0 && quad.uv_max.cmple(Vec2::ONE).all()));
+    }
+
+    #[test]
+    fn scale_multiplies_quad_sizes_but_not_uvs() {
+        let atlas = load_atlas();
+        let normal = atlas.layout("W", Vec2::ZERO, 1.0)[0];
+        let doubled = atlas.layout("W", Vec2::ZERO, 2.0)[0];
+
+        assert!(((doubled.max - doubled.min) - (normal.max - normal.min) * 2.0).abs().max_element() < EPSILON);
+        assert_eq!((normal.uv_min, normal.uv_max), (doubled.uv_min, doubled.uv_max));
+    }
+
+    #[test]
+    fn unknown_characters_use_the_fallback_glyph() {
+        let atlas = load_atlas();
+
+        assert_eq!(atlas.layout("é", Vec2::ZERO, 1.0), atlas.layout("?", Vec2::ZERO, 1.0));
+    }
+}
\ No newline at end of file
@@ -1,4 +1,14 @@
 pub mod vulkan_container;
 pub mod structs;
+pub mod shader_compiler;
+pub mod shader_watcher;
+pub mod pipeline_cache_file;
+pub mod pipeline_registry;
+pub mod glyph_atlas;
+pub mod ui_painter;
+pub mod render_graph;
 
-mod base_resources;
\ No newline at end of file
+mod base_resources;
+
+#[cfg(test)]
+mod render_tests;
\ No newline at end of file
@@ -0,0 +1,92 @@
+use std::{fs, io, path::PathBuf};
+
+use vulkano::device::physical::PhysicalDevice;
+
+const MAGIC: &[u8; 8] = b"SAEPIPE2";
+
+//Where the pipeline cache lives between runs. The file starts with our own header naming the device and driver it was made
+//with, a cache from another GPU or driver version is thrown away instead of being handed to the driver.
+pub struct PipelineCacheFile {
+    path: PathBuf,
+    header: Vec<u8>,
+}
+
+impl PipelineCacheFile {
+    //None when the platform has no user cache directory, the cache then only lives as long as the container.
+    pub fn new(physical_device: &PhysicalDevice) -> Option<Self> {
+        let path = dirs::cache_dir()?.join("SomewhereAnywhereEngine").join("pipeline_cache.bin");
+        let properties = physical_device.properties();
+        //Devices older than Vulkan 1.1 don't report their UUID, the pipeline cache UUID and the ids still tell them apart then.
+        let device_uuid = properties.device_uuid.unwrap_or_default();
+        let header = PipelineCacheFile::make_header(device_uuid, properties.pipeline_cache_uuid, properties.driver_version, properties.vendor_id, properties.device_id);
+
+        return Some(PipelineCacheFile { path, header });
+    }
+
+    pub fn get_path(&self) -> &PathBuf {
+        return &self.path;
+    }
+
+    //The cached data without our header, or None when there is no file or it was written for another device or driver.
+    pub fn load(&self) -> Option<Vec<u8>> {
+        let contents = fs::read(&self.path).ok()?;
+        return PipelineCacheFile::strip_header(&self.header, &contents).map(|data| data.to_vec());
+    }
+
+    //Written to a temporary file first so a crash halfway through never leaves a truncated cache behind.
+    pub fn save(&self, data: &[u8]) -> io::Result<()> {
+        if let Some(directory) = self.path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+
+        let temporary_path = self.path.with_extension("tmp");
+        fs::write(&temporary_path, [self.header.as_slice(), data].concat())?;
+        fs::rename(&temporary_path, &self.path)?;
+
+        return Ok(());
+    }
+
+    //The pipeline cache UUID is what the driver itself checks cache data against, it changes whenever the driver's cache format does.
+    fn make_header(device_uuid: [u8; 16], pipeline_cache_uuid: [u8; 16], driver_version: u32, vendor_id: u32, device_id: u32) -> Vec<u8> {
+        let mut header = MAGIC.to_vec();
+        header.extend_from_slice(&device_uuid);
+        header.extend_from_slice(&pipeline_cache_uuid);
+        header.extend_from_slice(&driver_version.to_le_bytes());
+        header.extend_from_slice(&vendor_id.to_le_bytes());
+        header.extend_from_slice(&device_id.to_le_bytes());
+
+        return header;
+    }
+
+    fn strip_header<'a>(header: &[u8], contents: &'a [u8]) -> Option<&'a [u8]> {
+        return contents.strip_prefix(header);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matching_header_returns_the_cache_data() {
+        let header = PipelineCacheFile::make_header([7; 16], [5; 16], 42, 0x10de, 0x2684);
+        let contents = [header.as_slice(), &[1, 2, 3]].concat();
+
+        assert_eq!(PipelineCacheFile::strip_header(&header, &contents), Some([1u8, 2, 3].as_slice()));
+    }
+
+    #[test]
+    fn other_device_or_driver_discards_the_cache() {
+        let header = PipelineCacheFile::make_header([7; 16], [5; 16], 42, 0x10de, 0x2684);
+        let contents = [header.as_slice(), &[1, 2, 3]].concat();
+
+        let other_driver = PipelineCacheFile::make_header([7; 16], [5; 16], 43, 0x10de, 0x2684);
+        let other_device = PipelineCacheFile::make_header([8; 16], [5; 16], 42, 0x10de, 0x2684);
+        let other_cache_format = PipelineCacheFile::make_header([7; 16], [6; 16], 42, 0x10de, 0x2684);
+
+        assert_eq!(PipelineCacheFile::strip_header(&other_driver, &contents), None);
+        assert_eq!(PipelineCacheFile::strip_header(&other_device, &contents), None);
+        assert_eq!(PipelineCacheFile::strip_header(&other_cache_format, &contents), None);
+        assert_eq!(PipelineCacheFile::strip_header(&header, &contents[..10]), None);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,49 @@
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use vulkano::pipeline::GraphicsPipeline;
+
+use crate::engine::vulkan::structs::pipeline_description::{PipelineDescription, PipelinePass, VertexInput};
+
+//Materials asking for a pipeline that doesn't exist are drawn with this one.
+pub const DEFAULT_PIPELINE: &str = "opaque";
+
+//Every pipeline the renderer can draw with, looked up by name. The description is kept next to the pipeline so it can be rebuilt, for example when one of its shaders changes.
+pub struct PipelineRegistry {
+    pipelines: HashMap<String, (PipelineDescription, Arc<GraphicsPipeline>)>,
+}
+
+impl PipelineRegistry {
+    pub fn new() -> Self {
+        return PipelineRegistry { pipelines: HashMap::new() };
+    }
+
+    //Replaces the pipeline registered under the same name, if there is one.
+    pub fn insert(&mut self, description: PipelineDescription, pipeline: Arc<GraphicsPipeline>) {
+        self.pipelines.insert(description.name.clone(), (description, pipeline));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Arc<GraphicsPipeline>> {
+        return self.pipelines.get(name).map(|(_, pipeline)| pipeline);
+    }
+
+    //Pipelines that don't draw meshes into the scene, like the skybox or the shadow pipeline, can't be picked by materials.
+    pub fn is_mesh_pipeline(&self, name: &str) -> bool {
+        return self.pipelines.get(name).is_some_and(|(description, _)| description.vertex_input == VertexInput::Mesh && description.pass == PipelinePass::Scene);
+    }
+
+    //Only objects that write depth in the scene cast shadows, so transparent ones don't.
+    pub fn casts_shadows(&self, name: &str) -> bool {
+        return self.pipelines.get(name).is_some_and(|(description, _)| description.depth_write);
+    }
+
+    pub fn is_transparent(&self, name: &str) -> bool {
+        return self.pipelines.get(name).is_some_and(|(description, _)| description.transparent);
+    }
+
+    pub fn descriptions_using_shader(&self, shader_path: &Path) -> Vec<PipelineDescription> {
+        return self.pipelines.values()
+            .filter(|(description, _)| description.uses_shader(shader_path))
+            .map(|(description, _)| description.clone())
+            .collect();
+    }
+}
\ No newline at end of file
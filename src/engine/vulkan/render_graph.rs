@@ -0,0 +1,413 @@
+use std::{collections::{BTreeMap, HashMap, HashSet}, error::Error, sync::Arc};
+
+use vulkano::{command_buffer::{AutoCommandBufferBuilder, PrimaryAutoCommandBuffer, RenderPassBeginInfo, SubpassBeginInfo, SubpassContents}, device::Device, format::{ClearValue, Format}, image::{view::ImageView, Image, ImageCreateInfo, ImageLayout, ImageType, ImageUsage, SampleCount}, memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator}, render_pass::{AttachmentDescription, AttachmentLoadOp, AttachmentReference, AttachmentStoreOp, Framebuffer, FramebufferCreateInfo, RenderPass, RenderPassCreateInfo, SubpassDescription}};
+
+use crate::engine::utils::logger::{LogLevel, Logger};
+
+//Name of the presented image. The graph never allocates it, the swapchain or the headless container hands it over.
+pub const PRESENT_ATTACHMENT: &str = "present";
+
+//Attachment sizes are relative to the presented image so the graph can allocate everything again when it changes size.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AttachmentSize {
+    Full,
+    //The presented size divided by this and rounded down, but never below a single pixel.
+    Divided(u32),
+    //Stays the same whatever the presented size, like shadow maps.
+    Fixed([u32; 2]),
+}
+
+impl AttachmentSize {
+    pub fn resolve(&self, extent: [u32; 2]) -> [u32; 2] {
+        return match self {
+            AttachmentSize::Full => extent,
+            AttachmentSize::Divided(divisor) => [(extent[0] / divisor).max(1), (extent[1] / divisor).max(1)],
+            AttachmentSize::Fixed(size) => *size,
+        };
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AttachmentDeclaration {
+    pub format: Format,
+    pub size: AttachmentSize,
+    pub samples: SampleCount,
+}
+
+impl AttachmentDeclaration {
+    pub fn new(format: Format, size: AttachmentSize) -> Self {
+        return AttachmentDeclaration { format, size, samples: SampleCount::Sample1 };
+    }
+
+    pub fn multisampled(format: Format, samples: SampleCount) -> Self {
+        return AttachmentDeclaration { format, size: AttachmentSize::Full, samples };
+    }
+}
+
+//What happens to an attachment when a pass starts drawing into it.
+#[derive(Clone, Copy, Debug)]
+pub enum AttachmentLoad {
+    Clear(ClearValue),
+    //Keeps what an earlier pass drew.
+    Load,
+    //The pass covers every pixel it cares about, so whatever was there is thrown away.
+    DontCare,
+}
+
+//One render pass of the graph, with every attachment it draws into and every attachment its shaders sample.
+#[derive(Clone, Debug)]
+pub struct PassDeclaration {
+    name: String,
+    color: Vec<(String, AttachmentLoad)>,
+    resolve: Vec<String>,
+    depth: Option<(String, AttachmentLoad)>,
+    reads: Vec<String>,
+}
+
+impl PassDeclaration {
+    pub fn new(name: &str) -> Self {
+        return PassDeclaration { name: name.to_string(), color: vec![], resolve: vec![], depth: None, reads: vec![] };
+    }
+
+    pub fn color(mut self, attachment: &str, load: AttachmentLoad) -> Self {
+        self.color.push((attachment.to_string(), load));
+        return self;
+    }
+
+    //Multisampled color attachments are resolved into these at the end of the pass, in the same order.
+    pub fn resolve(mut self, attachment: &str) -> Self {
+        self.resolve.push(attachment.to_string());
+        return self;
+    }
+
+    pub fn depth(mut self, attachment: &str, load: AttachmentLoad) -> Self {
+        self.depth = Some((attachment.to_string(), load));
+        return self;
+    }
+
+    //Sampled by the shaders of the pass, so another pass has to draw it and it has to be kept around afterwards.
+    pub fn reads(mut self, attachment: &str) -> Self {
+        self.reads.push(attachment.to_string());
+        return self;
+    }
+
+    fn writes(&self) -> impl Iterator<Item = &str> {
+        return self.color.iter().map(|(attachment, _)| attachment.as_str())
+            .chain(self.resolve.iter().map(|attachment| attachment.as_str()))
+            .chain(self.depth.iter().map(|(attachment, _)| attachment.as_str()));
+    }
+
+    //Loaded attachments need what an earlier pass drew, just like sampled ones.
+    fn needs_previous_contents(&self) -> impl Iterator<Item = &str> {
+        let loaded = self.color.iter().chain(self.depth.iter()).filter(|(_, load)| matches!(load, AttachmentLoad::Load)).map(|(attachment, _)| attachment.as_str());
+        return self.reads.iter().map(|attachment| attachment.as_str()).chain(loaded);
+    }
+}
+
+//What the graph worked out for an attachment from the way the passes use it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AttachmentPlan {
+    pub usage: ImageUsage,
+    //Attachments nothing reads afterwards are thrown away at the end of every pass and never need real memory behind them.
+    pub stored: bool,
+}
+
+//Every attachment and pass of a frame. Passes are recorded in whatever order the renderer needs, the description only says what each of them touches.
+pub struct RenderGraphDescription {
+    present_format: Format,
+    attachments: BTreeMap<String, AttachmentDeclaration>,
+    passes: Vec<PassDeclaration>,
+}
+
+impl RenderGraphDescription {
+    pub fn new(present_format: Format) -> Self {
+        return RenderGraphDescription { present_format, attachments: BTreeMap::new(), passes: vec![] };
+    }
+
+    pub fn add_attachment(&mut self, name: &str, attachment: AttachmentDeclaration) {
+        self.attachments.insert(name.to_string(), attachment);
+    }
+
+    pub fn add_pass(&mut self, pass: PassDeclaration) {
+        self.passes.push(pass);
+    }
+
+    fn get_attachment(&self, name: &str) -> Option<AttachmentDeclaration> {
+        if name == PRESENT_ATTACHMENT {
+            return Some(AttachmentDeclaration::new(self.present_format, AttachmentSize::Full));
+        }
+        return self.attachments.get(name).copied();
+    }
+
+    //Checks that the passes fit together and works out the usage of every attachment and whether it has to be stored.
+    pub fn plan(&self) -> Result<BTreeMap<String, AttachmentPlan>, String> {
+        let mut plans: BTreeMap<String, AttachmentPlan> = self.attachments.keys().map(|name| (name.clone(), AttachmentPlan { usage: ImageUsage::empty(), stored: false })).collect();
+        plans.insert(PRESENT_ATTACHMENT.to_string(), AttachmentPlan { usage: ImageUsage::COLOR_ATTACHMENT, stored: true });
+
+        let mut pass_names = HashSet::new();
+        let mut written = HashSet::new();
+        for pass in self.passes.iter() {
+            if !pass_names.insert(pass.name.as_str()) {
+                return Err(format!("Pass {} is declared twice", pass.name));
+            }
+
+            for attachment in pass.writes().chain(pass.reads.iter().map(|attachment| attachment.as_str())) {
+                if self.get_attachment(attachment).is_none() {
+                    return Err(format!("Pass {} uses attachment {} which was never declared", pass.name, attachment));
+                }
+            }
+            if let Some(attachment) = pass.reads.iter().find(|attachment| pass.writes().any(|written| written == attachment.as_str())) {
+                return Err(format!("Pass {} samples attachment {} while drawing into it", pass.name, attachment));
+            }
+
+            let mut samples = pass.color.iter().chain(pass.depth.iter()).map(|(attachment, _)| self.get_attachment(attachment).unwrap().samples);
+            let first_samples = samples.next().unwrap_or(SampleCount::Sample1);
+            if samples.any(|samples| samples != first_samples) {
+                return Err(format!("Attachments of pass {} have different sample counts", pass.name));
+            }
+            if !pass.resolve.is_empty() && (pass.resolve.len() != pass.color.len() || first_samples == SampleCount::Sample1 || pass.resolve.iter().any(|attachment| self.get_attachment(attachment).unwrap().samples != SampleCount::Sample1)) {
+                return Err(format!("Pass {} has to resolve every multisampled color attachment into a single sampled one", pass.name));
+            }
+
+            for attachment in pass.color.iter().map(|(attachment, _)| attachment).chain(pass.resolve.iter()) {
+                plans.get_mut(attachment).unwrap().usage |= ImageUsage::COLOR_ATTACHMENT;
+            }
+            if let Some((attachment, _)) = &pass.depth {
+                plans.get_mut(attachment).unwrap().usage |= ImageUsage::DEPTH_STENCIL_ATTACHMENT;
+            }
+            for attachment in pass.reads.iter() {
+                plans.get_mut(attachment).unwrap().usage |= ImageUsage::SAMPLED;
+            }
+            for attachment in pass.needs_previous_contents() {
+                plans.get_mut(attachment).unwrap().stored = true;
+            }
+            written.extend(pass.writes());
+        }
+
+        for pass in self.passes.iter() {
+            if let Some(attachment) = pass.needs_previous_contents().find(|attachment| !written.contains(attachment)) {
+                return Err(format!("Pass {} needs attachment {} but no pass draws into it", pass.name, attachment));
+            }
+        }
+
+        for (name, plan) in plans.iter_mut() {
+            if plan.usage.is_empty() {
+                return Err(format!("Attachment {} is declared but no pass uses it", name));
+            }
+            if !plan.stored {
+                plan.usage |= ImageUsage::TRANSIENT_ATTACHMENT;
+            }
+        }
+
+        return Ok(plans);
+    }
+}
+
+struct CompiledPass {
+    render_pass: Arc<RenderPass>,
+    framebuffers: Vec<Arc<Framebuffer>>,
+    clear_values: Vec<Option<ClearValue>>,
+}
+
+//The render passes of a description with their attachments and framebuffers allocated for every presented image.
+//It is built again from scratch whenever the presented images change. Pipelines made for the old passes keep working since the new ones are compatible.
+//Barriers and layout transitions between passes are left to the command buffer builder, which works them out from the images every pass touches.
+pub struct RenderGraph {
+    passes: HashMap<String, CompiledPass>,
+    attachments: HashMap<String, Vec<Arc<ImageView>>>,
+}
+
+impl RenderGraph {
+    pub fn new(logical_device: Arc<Device>, memory_allocator: Arc<StandardMemoryAllocator>, description: &RenderGraphDescription, present_views: &[Arc<ImageView>]) -> Result<Self, Box<dyn Error>> {
+        Logger::log(LogLevel::High, "vulkan_wrapper", &format!("Building render graph with {} passes...", description.passes.len()));
+
+        let plans = description.plan()?;
+        let [width, height, _] = present_views[0].image().extent();
+
+        let mut attachments = HashMap::from([(PRESENT_ATTACHMENT.to_string(), present_views.to_vec())]);
+        for (name, declaration) in description.attachments.iter() {
+            let [attachment_width, attachment_height] = declaration.size.resolve([width, height]);
+            let mut views = vec![];
+            for _ in present_views.iter() {
+                let image = Image::new(
+                    memory_allocator.clone(),
+                    ImageCreateInfo {
+                        image_type: ImageType::Dim2d,
+                        format: declaration.format,
+                        extent: [attachment_width, attachment_height, 1],
+                        usage: plans[name].usage,
+                        samples: declaration.samples,
+                        ..Default::default()
+                    },
+                    AllocationCreateInfo {
+                        memory_type_filter: MemoryTypeFilter::PREFER_DEVICE,
+                        ..Default::default()
+                    },
+                )?;
+                views.push(ImageView::new_default(image)?);
+            }
+            attachments.insert(name.clone(), views);
+        }
+
+        let mut passes = HashMap::new();
+        for pass in description.passes.iter() {
+            passes.insert(pass.name.clone(), RenderGraph::compile_pass(logical_device.clone(), description, &plans, &attachments, pass, present_views.len())?);
+        }
+
+        Logger::log(LogLevel::High, "vulkan_wrapper", "Render graph built.");
+        return Ok(RenderGraph { passes, attachments });
+    }
+
+    //Attachments of the render pass go color first, then resolve and depth last, in the order the pass declared them.
+    fn compile_pass(logical_device: Arc<Device>, description: &RenderGraphDescription, plans: &BTreeMap<String, AttachmentPlan>, attachments: &HashMap<String, Vec<Arc<ImageView>>>, pass: &PassDeclaration, image_count: usize) -> Result<CompiledPass, Box<dyn Error>> {
+        let mut attachment_descriptions = vec![];
+        let mut attachment_names = vec![];
+        let mut clear_values = vec![];
+        let mut reference = |attachment: &str, load: AttachmentLoad, layout: ImageLayout| {
+            let declaration = description.get_attachment(attachment).unwrap();
+            attachment_descriptions.push(AttachmentDescription {
+                format: declaration.format,
+                samples: declaration.samples,
+                load_op: match load {
+                    AttachmentLoad::Clear(_) => AttachmentLoadOp::Clear,
+                    AttachmentLoad::Load => AttachmentLoadOp::Load,
+                    AttachmentLoad::DontCare => AttachmentLoadOp::DontCare,
+                },
+                store_op: if plans[attachment].stored { AttachmentStoreOp::Store } else { AttachmentStoreOp::DontCare },
+                initial_layout: layout,
+                final_layout: layout,
+                ..Default::default()
+            });
+            clear_values.push(match load {
+                AttachmentLoad::Clear(clear_value) => Some(clear_value),
+                _ => None,
+            });
+            attachment_names.push(attachment.to_string());
+
+            return Some(AttachmentReference { attachment: attachment_names.len() as u32 - 1, layout, ..Default::default() });
+        };
+
+        let color_attachments = pass.color.iter().map(|(attachment, load)| reference(attachment, *load, ImageLayout::ColorAttachmentOptimal)).collect();
+        let color_resolve_attachments = pass.resolve.iter().map(|attachment| reference(attachment, AttachmentLoad::DontCare, ImageLayout::ColorAttachmentOptimal)).collect();
+        let depth_stencil_attachment = pass.depth.as_ref().and_then(|(attachment, load)| reference(attachment, *load, ImageLayout::DepthStencilAttachmentOptimal));
+
+        let render_pass = RenderPass::new(
+            logical_device,
+            RenderPassCreateInfo {
+                attachments: attachment_descriptions,
+                subpasses: vec![SubpassDescription {
+                    color_attachments,
+                    color_resolve_attachments,
+                    depth_stencil_attachment,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        )?;
+
+        let mut framebuffers = vec![];
+        for image_index in 0..image_count {
+            framebuffers.push(Framebuffer::new(
+                render_pass.clone(),
+                FramebufferCreateInfo {
+                    attachments: attachment_names.iter().map(|attachment| attachments[attachment][image_index].clone()).collect(),
+                    ..Default::default()
+                },
+            )?);
+        }
+
+        return Ok(CompiledPass { render_pass, framebuffers, clear_values });
+    }
+
+    //The getters panic on names the description never declared, those are mistakes in the renderer and not something to recover from.
+    pub fn get_render_pass(&self, pass: &str) -> Arc<RenderPass> {
+        return self.passes.get(pass).unwrap_or_else(|| panic!("Render graph has no pass named {}", pass)).render_pass.clone();
+    }
+
+    pub fn get_framebuffer(&self, pass: &str, image_index: usize) -> Arc<Framebuffer> {
+        return self.passes.get(pass).unwrap_or_else(|| panic!("Render graph has no pass named {}", pass)).framebuffers[image_index].clone();
+    }
+
+    pub fn get_attachment(&self, attachment: &str, image_index: usize) -> Arc<ImageView> {
+        return self.attachments.get(attachment).unwrap_or_else(|| panic!("Render graph has no attachment named {}", attachment))[image_index].clone();
+    }
+
+    //Starts the pass on the framebuffer of the presented image, clearing whatever the pass declared to be cleared.
+    pub fn begin_pass(&self, builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>, pass: &str, image_index: usize) {
+        let compiled_pass = self.passes.get(pass).unwrap_or_else(|| panic!("Render graph has no pass named {}", pass));
+
+        builder.begin_render_pass(
+                RenderPassBeginInfo {
+                    clear_values: compiled_pass.clear_values.clone(),
+                    ..RenderPassBeginInfo::framebuffer(compiled_pass.framebuffers[image_index].clone())
+                },
+                SubpassBeginInfo {
+                    contents: SubpassContents::Inline,
+                    ..Default::default()
+                },
+            ).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: AttachmentLoad = AttachmentLoad::Clear(ClearValue::Float([0.0, 0.0, 0.0, 1.0]));
+
+    fn scene_and_post_process(samples: SampleCount) -> RenderGraphDescription {
+        let mut description = RenderGraphDescription::new(Format::B8G8R8A8_SRGB);
+        description.add_attachment("multisampled", AttachmentDeclaration::multisampled(Format::R16G16B16A16_SFLOAT, samples));
+        description.add_attachment("depth", AttachmentDeclaration::multisampled(Format::D16_UNORM, samples));
+        description.add_attachment("hdr", AttachmentDeclaration::new(Format::R16G16B16A16_SFLOAT, AttachmentSize::Full));
+        description.add_pass(PassDeclaration::new("scene").color("multisampled", BLACK).resolve("hdr").depth("depth", AttachmentLoad::Clear(ClearValue::Depth(1.0))));
+        description.add_pass(PassDeclaration::new("output").color(PRESENT_ATTACHMENT, AttachmentLoad::DontCare).reads("hdr"));
+        return description;
+    }
+
+    #[test]
+    fn attachments_only_read_inside_their_pass_are_transient() {
+        let plans = scene_and_post_process(SampleCount::Sample4).plan().unwrap();
+
+        assert_eq!(plans["multisampled"], AttachmentPlan { usage: ImageUsage::COLOR_ATTACHMENT | ImageUsage::TRANSIENT_ATTACHMENT, stored: false });
+        assert_eq!(plans["depth"], AttachmentPlan { usage: ImageUsage::DEPTH_STENCIL_ATTACHMENT | ImageUsage::TRANSIENT_ATTACHMENT, stored: false });
+        assert_eq!(plans["hdr"], AttachmentPlan { usage: ImageUsage::COLOR_ATTACHMENT | ImageUsage::SAMPLED, stored: true });
+        assert!(plans[PRESENT_ATTACHMENT].stored);
+    }
+
+    #[test]
+    fn loaded_attachments_are_stored() {
+        let mut description = scene_and_post_process(SampleCount::Sample4);
+        description.add_attachment("accumulated", AttachmentDeclaration::new(Format::R16G16B16A16_SFLOAT, AttachmentSize::Divided(2)));
+        description.add_pass(PassDeclaration::new("accumulate").color("accumulated", AttachmentLoad::Load));
+
+        assert_eq!(description.plan().unwrap()["accumulated"], AttachmentPlan { usage: ImageUsage::COLOR_ATTACHMENT, stored: true });
+    }
+
+    #[test]
+    fn broken_graphs_are_rejected() {
+        let mut unknown = scene_and_post_process(SampleCount::Sample4);
+        unknown.add_pass(PassDeclaration::new("blur").color("hdr", AttachmentLoad::DontCare).reads("bloom"));
+        assert!(unknown.plan().unwrap_err().contains("never declared"));
+
+        let mut feedback = scene_and_post_process(SampleCount::Sample4);
+        feedback.add_pass(PassDeclaration::new("blur").color("hdr", AttachmentLoad::DontCare).reads("hdr"));
+        assert!(feedback.plan().unwrap_err().contains("while drawing into it"));
+
+        let mut never_drawn = scene_and_post_process(SampleCount::Sample4);
+        never_drawn.add_attachment("bloom", AttachmentDeclaration::new(Format::R16G16B16A16_SFLOAT, AttachmentSize::Divided(2)));
+        never_drawn.add_pass(PassDeclaration::new("composite").color("hdr", AttachmentLoad::DontCare).reads("bloom"));
+        assert!(never_drawn.plan().unwrap_err().contains("no pass draws into it"));
+
+        //Without multisampling there is nothing to resolve.
+        assert!(scene_and_post_process(SampleCount::Sample1).plan().unwrap_err().contains("resolve"));
+    }
+
+    #[test]
+    fn sizes_follow_the_presented_image_unless_fixed() {
+        assert_eq!(AttachmentSize::Divided(4).resolve([322, 241]), [80, 60]);
+        assert_eq!(AttachmentSize::Divided(64).resolve([40, 30]), [1, 1]);
+        assert_eq!(AttachmentSize::Full.resolve([40, 30]), [40, 30]);
+        assert_eq!(AttachmentSize::Fixed([2048, 1024]).resolve([40, 30]), [2048, 1024]);
+    }
+}
\ No newline at end of file
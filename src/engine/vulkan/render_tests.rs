@@ -0,0 +1,342 @@
+//Golden image tests. Scenes are rendered with a headless container and compared against the reference PNGs in tests/golden.
+//They need a Vulkan device (lavapipe works fine) so they are ignored by default, run them with: cargo test -- --ignored
+//Set SAE_BLESS_GOLDEN=1 to (re)write the references from the current output.
+use std::{fs, path::PathBuf};
+
+use glam::{vec2, vec3, vec4};
+use image::{Rgba, RgbaImage};
+
+use crate::engine::{app::App, components::{command_bus::command_bus::{CommandBus, CommandType}, entities::entity::EntityType}, utils::structs::{light::DirectionalLight, material::{Material, MaterialHandle}, post_process::{PostProcessChain, PostProcessEffect}, shadow::ShadowSettings, skybox::SkyboxSource, text::TextSection, transform::Transform}, vulkan::{structs::{render_settings::RenderSettings, viewport::ViewportInfo}, vulkan_container::VulkanContainer}};
+
+const GOLDEN_DIR: &str = "tests/golden";
+const OUTPUT_DIR: &str = "target/golden";
+const RENDER_EXTENT: [f32; 2] = [320.0, 240.0];
+
+//Software and hardware rasterizers disagree slightly on edges and filtering, so allow a little slack per channel and a few outlier pixels.
+const CHANNEL_TOLERANCE: u8 = 8;
+const MAX_MISMATCHED_RATIO: f64 = 0.002;
+
+struct ImageComparison {
+    mismatched_pixels: usize,
+    total_pixels: usize,
+    diff: RgbaImage,
+}
+
+impl ImageComparison {
+    fn mismatched_ratio(&self) -> f64 {
+        return self.mismatched_pixels as f64 / self.total_pixels as f64;
+    }
+}
+
+fn compare_images(expected: &RgbaImage, actual: &RgbaImage, tolerance: u8) -> ImageComparison {
+    let mut diff = RgbaImage::new(actual.width(), actual.height());
+    let mut mismatched_pixels = 0;
+
+    for (x, y, actual_pixel) in actual.enumerate_pixels() {
+        let expected_pixel = expected.get_pixel(x, y);
+        let within_tolerance = expected_pixel.0.iter()
+            .zip(actual_pixel.0.iter())
+            .all(|(expected_channel, actual_channel)| expected_channel.abs_diff(*actual_channel) <= tolerance);
+
+        //Mismatches are painted red on top of a dimmed copy of the actual frame so they are easy to spot.
+        let diff_pixel = if within_tolerance {
+            let luminance = ((actual_pixel[0] as u16 + actual_pixel[1] as u16 + actual_pixel[2] as u16) / 12) as u8;
+            Rgba([luminance, luminance, luminance, 255])
+        } else {
+            mismatched_pixels += 1;
+            Rgba([255, 0, 0, 255])
+        };
+        diff.put_pixel(x, y, diff_pixel);
+    }
+
+    return ImageComparison {
+        mismatched_pixels,
+        total_pixels: (actual.width() * actual.height()) as usize,
+        diff,
+    };
+}
+
+fn render_scene(name: &str, build_scene: impl FnOnce(&mut CommandBus)) -> RgbaImage {
+    fs::create_dir_all(OUTPUT_DIR).unwrap();
+
+    let viewport_info = ViewportInfo::new([0.0, 0.0], RENDER_EXTENT);
+    let vulkan_container = VulkanContainer::new_headless(&viewport_info, RenderSettings::default());
+    let mut command_bus = App::create_command_bus(vulkan_container, None);
+
+    build_scene(&mut command_bus);
+    command_bus.update_managers();
+
+    let actual_path = PathBuf::from(OUTPUT_DIR).join(format!("{}.actual.png", name));
+    command_bus.capture_frame(&actual_path.to_string_lossy()).expect("Headless frame was not captured");
+
+    return image::open(&actual_path).unwrap().to_rgba8();
+}
+
+fn assert_matches_golden(name: &str, actual: &RgbaImage) {
+    let golden_path = PathBuf::from(GOLDEN_DIR).join(format!("{}.png", name));
+
+    if std::env::var("SAE_BLESS_GOLDEN").is_ok() {
+        fs::create_dir_all(GOLDEN_DIR).unwrap();
+        actual.save(&golden_path).unwrap();
+        return;
+    }
+
+    let expected = match image::open(&golden_path) {
+        Ok(expected) => expected.to_rgba8(),
+        Err(error) => panic!("Missing reference image {} ({}). Run with SAE_BLESS_GOLDEN=1 to create it.", golden_path.display(), error),
+    };
+
+    assert_eq!(expected.dimensions(), actual.dimensions(), "Reference image {} has a different size than the rendered frame", golden_path.display());
+
+    let comparison = compare_images(&expected, actual, CHANNEL_TOLERANCE);
+    if comparison.mismatched_ratio() > MAX_MISMATCHED_RATIO {
+        let diff_path = PathBuf::from(OUTPUT_DIR).join(format!("{}.diff.png", name));
+        comparison.diff.save(&diff_path).unwrap();
+
+        panic!(
+            "{} of {} pixels differ from {} by more than {} per channel, diff written to {}",
+            comparison.mismatched_pixels,
+            comparison.total_pixels,
+            golden_path.display(),
+            CHANNEL_TOLERANCE,
+            diff_path.display(),
+        );
+    }
+}
+
+fn create_player(command_bus: &mut CommandBus) {
+    let player_transform = Transform::from_euler(
+        vec3(0.0, 0.0, -5.0),
+        vec3(0.0, 0.0, 0.0),
+        vec3(1.0, 1.0, 1.0),
+    );
+    command_bus.send_command(CommandType::CreateEntity(EntityType::PlayerEntity(player_transform)));
+}
+
+#[test]
+#[ignore = "needs a Vulkan device, run with cargo test -- --ignored"]
+fn golden_default_scene() {
+    let actual = render_scene("default_scene", App::create_default_scene);
+    assert_matches_golden("default_scene", &actual);
+}
+
+#[test]
+#[ignore = "needs a Vulkan device, run with cargo test -- --ignored"]
+fn golden_empty_scene() {
+    let actual = render_scene("empty_scene", create_player);
+    assert_matches_golden("empty_scene", &actual);
+}
+
+#[test]
+#[ignore = "needs a Vulkan device, run with cargo test -- --ignored"]
+fn golden_single_cube() {
+    let actual = render_scene("single_cube", |command_bus| {
+        create_player(command_bus);
+
+        let cube_transform = Transform::from_euler(
+            vec3(0.0, 0.0, 2.0),
+            vec3(0.0, 0.0, 0.0),
+            vec3(1.0, 1.0, 1.0),
+        );
+        command_bus.send_command(CommandType::CreateEntity(EntityType::CubeEntity(cube_transform, MaterialHandle::default())));
+    });
+    assert_matches_golden("single_cube", &actual);
+}
+
+#[test]
+#[ignore = "needs a Vulkan device, run with cargo test -- --ignored"]
+fn golden_rotated_scaled_cube() {
+    let actual = render_scene("rotated_scaled_cube", |command_bus| {
+        create_player(command_bus);
+
+        let cube_transform = Transform::from_euler(
+            vec3(0.0, 0.0, 2.0),
+            vec3(0.4, 0.7, 0.2),
+            vec3(1.5, 0.75, 1.0),
+        );
+        command_bus.send_command(CommandType::CreateEntity(EntityType::CubeEntity(cube_transform, MaterialHandle::default())));
+    });
+    assert_matches_golden("rotated_scaled_cube", &actual);
+}
+
+#[test]
+#[ignore = "needs a Vulkan device, run with cargo test -- --ignored"]
+fn golden_untextured_material() {
+    let actual = render_scene("untextured_material", |command_bus| {
+        create_player(command_bus);
+
+        command_bus.send_command(CommandType::CreateMaterial(MaterialHandle::new("red"), Material { base_color: vec4(0.8, 0.1, 0.1, 1.0), ..Default::default() }));
+
+        let cube_transform = Transform::from_euler(
+            vec3(0.0, 0.0, 2.0),
+            vec3(0.4, 0.7, 0.0),
+            vec3(1.0, 1.0, 1.0),
+        );
+        command_bus.send_command(CommandType::CreateEntity(EntityType::CubeEntity(cube_transform, MaterialHandle::new("red"))));
+    });
+    assert_matches_golden("untextured_material", &actual);
+}
+
+//One cube per pipeline, each of them gets its own pipeline bind within the same frame.
+#[test]
+#[ignore = "needs a Vulkan device, run with cargo test -- --ignored"]
+fn golden_unlit_and_wireframe_pipelines() {
+    let actual = render_scene("unlit_and_wireframe_pipelines", |command_bus| {
+        create_player(command_bus);
+
+        command_bus.send_command(CommandType::CreateMaterial(MaterialHandle::new("unlit"), Material { base_color: vec4(0.1, 0.6, 0.9, 1.0), pipeline: "unlit".to_string(), ..Default::default() }));
+        command_bus.send_command(CommandType::CreateMaterial(MaterialHandle::new("wireframe"), Material { pipeline: "wireframe".to_string(), ..Default::default() }));
+
+        let unlit_transform = Transform::from_euler(vec3(-1.5, 0.0, 2.0), vec3(0.4, 0.7, 0.0), vec3(1.0, 1.0, 1.0));
+        let wireframe_transform = Transform::from_euler(vec3(1.5, 0.0, 2.0), vec3(0.4, 0.7, 0.0), vec3(1.0, 1.0, 1.0));
+        command_bus.send_command(CommandType::CreateEntity(EntityType::CubeEntity(unlit_transform, MaterialHandle::new("unlit"))));
+        command_bus.send_command(CommandType::CreateEntity(EntityType::CubeEntity(wireframe_transform, MaterialHandle::new("wireframe"))));
+    });
+    assert_matches_golden("unlit_and_wireframe_pipelines", &actual);
+}
+
+//Two see-through cubes in front of an opaque one, the far one has to be blended first for the near one to show both behind it.
+#[test]
+#[ignore = "needs a Vulkan device, run with cargo test -- --ignored"]
+fn golden_transparent_cubes() {
+    let actual = render_scene("transparent_cubes", |command_bus| {
+        create_player(command_bus);
+
+        command_bus.send_command(CommandType::CreateMaterial(MaterialHandle::new("red"), Material { base_color: vec4(0.8, 0.1, 0.1, 1.0), ..Default::default() }));
+        command_bus.send_command(CommandType::CreateMaterial(MaterialHandle::new("glass_blue"), Material { base_color: vec4(0.1, 0.3, 0.9, 0.4), pipeline: "alpha_blended".to_string(), ..Default::default() }));
+        command_bus.send_command(CommandType::CreateMaterial(MaterialHandle::new("glass_green"), Material { base_color: vec4(0.1, 0.9, 0.2, 0.4), pipeline: "alpha_blended".to_string(), ..Default::default() }));
+
+        let opaque_transform = Transform::from_euler(vec3(0.0, 0.0, -1.0), vec3(0.0, 0.0, 0.0), vec3(1.0, 1.0, 1.0));
+        let far_transform = Transform::from_euler(vec3(0.5, 0.3, 0.5), vec3(0.4, 0.7, 0.0), vec3(1.0, 1.0, 1.0));
+        let near_transform = Transform::from_euler(vec3(-0.5, -0.3, 2.0), vec3(0.4, 0.7, 0.0), vec3(1.0, 1.0, 1.0));
+        command_bus.send_command(CommandType::CreateEntity(EntityType::CubeEntity(near_transform, MaterialHandle::new("glass_green"))));
+        command_bus.send_command(CommandType::CreateEntity(EntityType::CubeEntity(opaque_transform, MaterialHandle::new("red"))));
+        command_bus.send_command(CommandType::CreateEntity(EntityType::CubeEntity(far_transform, MaterialHandle::new("glass_blue"))));
+    });
+    assert_matches_golden("transparent_cubes", &actual);
+}
+
+//The sky fills the background and the mirror cube reflects it.
+#[test]
+#[ignore = "needs a Vulkan device, run with cargo test -- --ignored"]
+fn golden_skybox_reflection() {
+    let actual = render_scene("skybox_reflection", |command_bus| {
+        create_player(command_bus);
+
+        command_bus.send_command(CommandType::SetSkybox(Some(SkyboxSource::Equirectangular("src/engine/vulkan/base_resources/default_sky.png".to_string()))));
+        command_bus.send_command(CommandType::CreateMaterial(MaterialHandle::new("mirror"), Material { reflectivity: 0.9, ..Default::default() }));
+
+        let cube_transform = Transform::from_euler(vec3(0.0, 0.0, 2.0), vec3(0.4, 0.7, 0.0), vec3(1.0, 1.0, 1.0));
+        command_bus.send_command(CommandType::CreateEntity(EntityType::CubeEntity(cube_transform, MaterialHandle::new("mirror"))));
+    });
+    assert_matches_golden("skybox_reflection", &actual);
+}
+
+//Text is drawn over the cube, at two scales and with a line break.
+#[test]
+#[ignore = "needs a Vulkan device, run with cargo test -- --ignored"]
+fn golden_text_overlay() {
+    let actual = render_scene("text_overlay", |command_bus| {
+        create_player(command_bus);
+
+        let cube_transform = Transform::from_euler(vec3(0.0, 0.0, 0.0), vec3(0.4, 0.7, 0.0), vec3(1.0, 1.0, 1.0));
+        command_bus.send_command(CommandType::CreateEntity(EntityType::CubeEntity(cube_transform, MaterialHandle::default())));
+        command_bus.send_command(CommandType::DrawText(TextSection::new("Hello, text!\nSecond line", vec2(8.0, 8.0))));
+        command_bus.send_command(CommandType::DrawText(TextSection { color: vec4(1.0, 0.3, 0.2, 1.0), scale: 2.0, ..TextSection::new("BIG", vec2(100.0, 160.0)) }));
+    });
+    assert_matches_golden("text_overlay", &actual);
+}
+
+//A cube glowing far past white bleeds light onto the background around it.
+#[test]
+#[ignore = "needs a Vulkan device, run with cargo test -- --ignored"]
+fn golden_emissive_bloom() {
+    let actual = render_scene("emissive_bloom", |command_bus| {
+        create_player(command_bus);
+
+        command_bus.send_command(CommandType::CreateMaterial(MaterialHandle::new("glowing"), Material { base_color: vec4(0.1, 0.1, 0.1, 1.0), emissive: vec3(4.0, 1.5, 0.3), ..Default::default() }));
+        command_bus.send_command(CommandType::SetPostProcessChain(PostProcessChain::empty().with(PostProcessEffect::Bloom { threshold: 1.0, intensity: 0.1 }).with(PostProcessEffect::Tonemap { exposure: 1.0 })));
+
+        let cube_transform = Transform::from_euler(vec3(0.0, 0.0, 0.0), vec3(0.4, 0.7, 0.0), vec3(0.6, 0.6, 0.6));
+        command_bus.send_command(CommandType::CreateEntity(EntityType::CubeEntity(cube_transform, MaterialHandle::new("glowing"))));
+    });
+    assert_matches_golden("emissive_bloom", &actual);
+}
+
+//A cube floating above a floor throws a soft edged shadow onto it, split over two cascades.
+#[test]
+#[ignore = "needs a Vulkan device, run with cargo test -- --ignored"]
+fn golden_directional_shadow() {
+    let actual = render_scene("directional_shadow", |command_bus| {
+        create_player(command_bus);
+
+        command_bus.send_command(CommandType::SetDirectionalLight(DirectionalLight::new(vec3(0.3, -1.0, 0.2), vec3(1.0, 1.0, 1.0), 1.0)));
+        command_bus.send_command(CommandType::SetShadowSettings(ShadowSettings::new(1024, 0.05, 2, 20.0)));
+
+        let floor_transform = Transform::from_euler(vec3(0.0, -1.5, 0.0), vec3(0.0, 0.0, 0.0), vec3(6.0, 0.2, 6.0));
+        command_bus.send_command(CommandType::CreateEntity(EntityType::CubeEntity(floor_transform, MaterialHandle::default())));
+        let cube_transform = Transform::from_euler(vec3(0.0, 0.0, 0.0), vec3(0.4, 0.7, 0.0), vec3(0.6, 0.6, 0.6));
+        command_bus.send_command(CommandType::CreateEntity(EntityType::CubeEntity(cube_transform, MaterialHandle::default())));
+    });
+    assert_matches_golden("directional_shadow", &actual);
+}
+
+//Every effect of the chain in a row. The identity lookup table leaves colors as they are, so only the other effects show.
+#[test]
+#[ignore = "needs a Vulkan device, run with cargo test -- --ignored"]
+fn golden_post_process_chain() {
+    let actual = render_scene("post_process_chain", |command_bus| {
+        create_player(command_bus);
+
+        let chain = PostProcessChain::default()
+            .with(PostProcessEffect::ColorGrading { lut_path: "src/engine/vulkan/base_resources/identity_lut.png".to_string() })
+            .with(PostProcessEffect::Fxaa)
+            .with(PostProcessEffect::Vignette { intensity: 0.8, radius: 0.6 })
+            .with(PostProcessEffect::Gamma { gamma: 1.2 });
+        command_bus.send_command(CommandType::SetPostProcessChain(chain));
+
+        let cube_transform = Transform::from_euler(vec3(0.0, 0.0, 0.0), vec3(0.4, 0.7, 0.0), vec3(1.0, 1.0, 1.0));
+        command_bus.send_command(CommandType::CreateEntity(EntityType::CubeEntity(cube_transform, MaterialHandle::default())));
+    });
+    assert_matches_golden("post_process_chain", &actual);
+}
+
+//Moving an entity after its vulkan object exists has to end up on screen, so this must look exactly like the single cube scene.
+#[test]
+#[ignore = "needs a Vulkan device, run with cargo test -- --ignored"]
+fn golden_moved_cube_matches_single_cube() {
+    let actual = render_scene("moved_cube", |command_bus| {
+        create_player(command_bus);
+
+        let cube_transform = Transform::from_euler(
+            vec3(-2.0, 1.0, 0.0),
+            vec3(0.0, 0.0, 0.0),
+            vec3(1.0, 1.0, 1.0),
+        );
+        command_bus.send_command(CommandType::CreateEntity(EntityType::CubeEntity(cube_transform, MaterialHandle::default())));
+        command_bus.update_managers();
+
+        let moved_transform = Transform::from_euler(
+            vec3(0.0, 0.0, 2.0),
+            vec3(0.0, 0.0, 0.0),
+            vec3(1.0, 1.0, 1.0),
+        );
+        command_bus.send_command(CommandType::SetEntityTransform(1, moved_transform));
+    });
+    assert_matches_golden("single_cube", &actual);
+}
+
+#[test]
+fn compare_images_respects_tolerance() {
+    let expected = RgbaImage::from_pixel(4, 4, Rgba([100, 100, 100, 255]));
+    let mut actual = RgbaImage::from_pixel(4, 4, Rgba([104, 96, 100, 255]));
+
+    assert_eq!(compare_images(&expected, &actual, 8).mismatched_pixels, 0);
+
+    actual.put_pixel(1, 2, Rgba([200, 100, 100, 255]));
+    let comparison = compare_images(&expected, &actual, 8);
+
+    assert_eq!(comparison.mismatched_pixels, 1);
+    assert_eq!(comparison.diff.get_pixel(1, 2), &Rgba([255, 0, 0, 255]));
+    assert_ne!(comparison.diff.get_pixel(0, 0), &Rgba([255, 0, 0, 255]));
+}
\ No newline at end of file
@@ -0,0 +1,66 @@
+use std::{error::Error, fs, path::Path};
+
+use shaderc::{CompileOptions, Compiler, EnvVersion, ShaderKind, TargetEnv};
+
+use crate::engine::utils::logger::{LogLevel, Logger};
+
+//Turns the GLSL sources in src/shaders into SPIR-V while the engine runs, so there are no prebuilt .spv files that can go stale or missing.
+pub struct ShaderCompiler {
+    compiler: Compiler,
+}
+
+impl ShaderCompiler {
+    pub fn new() -> Result<Self, Box<dyn Error>> {
+        let compiler = Compiler::new().ok_or("Failed to initialize the shader compiler")?;
+        return Ok(ShaderCompiler { compiler });
+    }
+
+    //The stage is picked from the file extension, like glslangValidator does.
+    pub fn compile(&self, path: &Path) -> Result<Vec<u32>, Box<dyn Error>> {
+        let shader_kind = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("vert") => ShaderKind::Vertex,
+            Some("frag") => ShaderKind::Fragment,
+            _ => return Err(format!("Can't tell the shader stage of {} from its extension", path.display()).into()),
+        };
+
+        let source = fs::read_to_string(path)?;
+
+        let mut options = CompileOptions::new().ok_or("Failed to create shader compile options")?;
+        options.set_target_env(TargetEnv::Vulkan, EnvVersion::Vulkan1_0 as u32);
+
+        let artifact = self.compiler.compile_into_spirv(&source, shader_kind, &path.to_string_lossy(), "main", Some(&options))?;
+        if artifact.get_num_warnings() > 0 {
+            Logger::log(LogLevel::Medium, "shader_compiler", &artifact.get_warning_messages());
+        }
+
+        return Ok(artifact.as_binary().to_vec());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::engine::vulkan::structs::pipeline_description::{shader_path, SHADER_DIRECTORY};
+
+    use super::*;
+
+    //Catches shader mistakes without needing a Vulkan device.
+    #[test]
+    fn compiles_every_shader_in_the_shader_directory() {
+        let compiler = ShaderCompiler::new().unwrap();
+
+        for entry in fs::read_dir(SHADER_DIRECTORY).unwrap() {
+            let path: PathBuf = entry.unwrap().path();
+            if let Err(error) = compiler.compile(&path) {
+                panic!("{} failed to compile: {}", path.display(), error);
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_shader_stages() {
+        let compiler = ShaderCompiler::new().unwrap();
+        assert!(compiler.compile(Path::new(&shader_path("shader.glsl"))).is_err());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,41 @@
+use std::{collections::HashSet, error::Error, path::{Path, PathBuf}, sync::mpsc::{self, Receiver}};
+
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+
+//Keeps an eye on the shader sources so pipelines can be rebuilt when one of them is saved.
+pub struct ShaderWatcher {
+    //Only kept alive, dropping it stops the watching.
+    _watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+}
+
+impl ShaderWatcher {
+    pub fn new(shader_directory: &Path) -> Result<Self, Box<dyn Error>> {
+        let (sender, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = sender.send(event);
+        })?;
+        watcher.watch(shader_directory, RecursiveMode::NonRecursive)?;
+
+        return Ok(ShaderWatcher { _watcher: watcher, events });
+    }
+
+    //Every shader source written to since the last call. Editors tend to save in several steps, those all collapse into one entry.
+    pub fn changed_shaders(&self) -> HashSet<PathBuf> {
+        let mut changed_shaders = HashSet::new();
+
+        for event in self.events.try_iter().flatten() {
+            if !(event.kind.is_modify() || event.kind.is_create()) {
+                continue;
+            }
+
+            for path in event.paths {
+                if matches!(path.extension().and_then(|extension| extension.to_str()), Some("vert") | Some("frag")) {
+                    changed_shaders.insert(path);
+                }
+            }
+        }
+
+        return changed_shaders;
+    }
+}
\ No newline at end of file
@@ -0,0 +1,32 @@
+//Bloom never blurs through more levels than this, the smallest ones add little but cost a pass each.
+pub const MAX_BLOOM_LEVELS: usize = 6;
+
+//Sizes of the mip chain bloom blurs through. The first level is half the size of the image, every other one half the size of the level before,
+//until a side reaches a single pixel or there are enough levels.
+pub fn bloom_level_extents(extent: [u32; 2]) -> Vec<[u32; 2]> {
+    let mut level_extent = [(extent[0] / 2).max(1), (extent[1] / 2).max(1)];
+    let mut level_extents = vec![level_extent];
+
+    while level_extents.len() < MAX_BLOOM_LEVELS && level_extent[0] > 1 && level_extent[1] > 1 {
+        level_extent = [(level_extent[0] / 2).max(1), (level_extent[1] / 2).max(1)];
+        level_extents.push(level_extent);
+    }
+
+    return level_extents;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_halve_until_the_limit() {
+        assert_eq!(bloom_level_extents([320, 240]), vec![[160, 120], [80, 60], [40, 30], [20, 15], [10, 7], [5, 3]]);
+    }
+
+    #[test]
+    fn tiny_images_stop_at_a_single_pixel() {
+        assert_eq!(bloom_level_extents([8, 2]), vec![[4, 1]]);
+        assert_eq!(bloom_level_extents([1, 1]), vec![[1, 1]]);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,17 @@
+use glam::Vec3;
+
+//One end of a debug line, there are no normals or texture coordinates since debug lines are never lit or textured.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, bytemuck::Pod, bytemuck::Zeroable, vulkano::pipeline::graphics::vertex_input::Vertex)]
+pub struct DebugVertex {
+    #[format(R32G32B32_SFLOAT)]
+    position: Vec3,
+    #[format(R32G32B32_SFLOAT)]
+    color: Vec3,
+}
+
+impl DebugVertex {
+    pub fn new(position: Vec3, color: Vec3) -> Self {
+        return DebugVertex { position, color };
+    }
+}
\ No newline at end of file
@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use vulkano::sync::{future::FenceSignalFuture, GpuFuture};
+
+pub type FrameFence = Arc<FenceSignalFuture<Box<dyn GpuFuture>>>;
+
+//Everything one of the frames in flight owns. The slot can only be reused once the fence of its last submission has signaled.
+pub struct FrameInFlight {
+    fence: Option<FrameFence>,
+}
+
+impl FrameInFlight {
+    pub fn new() -> Self {
+        return FrameInFlight { fence: None };
+    }
+
+    pub fn wait(&self) {
+        if let Some(fence) = &self.fence {
+            fence.wait(None).unwrap();
+        }
+    }
+
+    pub fn get_fence(&self) -> Option<FrameFence> {
+        return self.fence.clone();
+    }
+
+    pub fn set_fence(&mut self, fence: Option<FrameFence>) {
+        self.fence = fence;
+    }
+}
\ No newline at end of file
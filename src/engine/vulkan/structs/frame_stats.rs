@@ -0,0 +1,14 @@
+use std::time::Duration;
+
+//Numbers about the last recorded frame, handy for benchmarks and debug overlays.
+#[derive(Clone, Debug, Default)]
+pub struct FrameStats {
+    pub objects: usize,
+    pub culled_objects: usize,
+    pub draw_calls: usize,
+    //How often a different material had to be bound, draws are sorted by material to keep this low.
+    pub material_binds: usize,
+    //How often a different pipeline had to be bound, draws are grouped by pipeline before material.
+    pub pipeline_binds: usize,
+    pub recording_time: Duration,
+}
\ No newline at end of file
@@ -0,0 +1,25 @@
+use std::sync::Arc;
+
+use vulkano::descriptor_set::DescriptorSet;
+
+use crate::engine::vulkan::glyph_atlas::GlyphAtlas;
+
+//A font as the renderer uses it, the glyph atlas for laying out text and the descriptor set its texture is bound through.
+pub struct GpuFont {
+    atlas: GlyphAtlas,
+    descriptor_set: Arc<DescriptorSet>,
+}
+
+impl GpuFont {
+    pub fn new(atlas: GlyphAtlas, descriptor_set: Arc<DescriptorSet>) -> Self {
+        return GpuFont { atlas, descriptor_set };
+    }
+
+    pub fn get_atlas(&self) -> &GlyphAtlas {
+        return &self.atlas;
+    }
+
+    pub fn get_descriptor_set(&self) -> Arc<DescriptorSet> {
+        return self.descriptor_set.clone();
+    }
+}
\ No newline at end of file
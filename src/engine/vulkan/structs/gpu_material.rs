@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use vulkano::descriptor_set::DescriptorSet;
+
+use crate::engine::{utils::structs::material::MaterialHandle, vulkan::structs::texture::Texture};
+
+//A material as the renderer uses it, its texture, sampler and uniforms are all bound through one descriptor set.
+#[derive(Debug)]
+pub struct GpuMaterial {
+    handle: MaterialHandle,
+    pipeline: String,
+    texture: Arc<Texture>,
+    descriptor_set: Arc<DescriptorSet>,
+}
+
+impl GpuMaterial {
+    pub fn new(handle: MaterialHandle, pipeline: String, texture: Arc<Texture>, descriptor_set: Arc<DescriptorSet>) -> Self {
+        return GpuMaterial { handle, pipeline, texture, descriptor_set };
+    }
+
+    pub fn get_handle(&self) -> &MaterialHandle {
+        return &self.handle;
+    }
+
+    pub fn get_pipeline(&self) -> &str {
+        return &self.pipeline;
+    }
+
+    pub fn get_texture(&self) -> &Arc<Texture> {
+        return &self.texture;
+    }
+
+    pub fn get_descriptor_set(&self) -> Arc<DescriptorSet> {
+        return self.descriptor_set.clone();
+    }
+}
\ No newline at end of file
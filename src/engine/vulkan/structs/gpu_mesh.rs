@@ -0,0 +1,42 @@
+use vulkano::buffer::Subbuffer;
+
+use crate::engine::{utils::structs::bounding_sphere::BoundingSphere, vulkan::structs::vertex::Vertex};
+
+#[derive(Debug)]
+pub struct GpuMesh {
+    name: String,
+    cache_key: String,
+    vertex_buffer: Subbuffer<[Vertex]>,
+    index_buffer: Subbuffer<[u32]>,
+    bounds: BoundingSphere,
+}
+
+impl GpuMesh {
+    pub fn new(name: &str, cache_key: String, vertex_buffer: Subbuffer<[Vertex]>, index_buffer: Subbuffer<[u32]>, bounds: BoundingSphere) -> Self {
+        return GpuMesh { name: name.to_string(), cache_key, vertex_buffer, index_buffer, bounds };
+    }
+
+    pub fn get_name(&self) -> &str {
+        return &self.name;
+    }
+
+    pub fn get_cache_key(&self) -> &str {
+        return &self.cache_key;
+    }
+
+    pub fn get_vertex_buffer(&self) -> &Subbuffer<[Vertex]> {
+        return &self.vertex_buffer;
+    }
+
+    pub fn get_index_buffer(&self) -> &Subbuffer<[u32]> {
+        return &self.index_buffer;
+    }
+
+    pub fn get_bounds(&self) -> &BoundingSphere {
+        return &self.bounds;
+    }
+
+    pub fn get_index_count(&self) -> u32 {
+        return self.index_buffer.len() as u32;
+    }
+}
\ No newline at end of file
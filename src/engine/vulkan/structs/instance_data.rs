@@ -0,0 +1,15 @@
+use glam::Mat4;
+
+//Per instance vertex data, one model matrix for every object drawn in an instanced batch.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, bytemuck::Pod, bytemuck::Zeroable, vulkano::pipeline::graphics::vertex_input::Vertex)]
+pub struct InstanceData {
+    #[format(R32G32B32A32_SFLOAT)]
+    model: [[f32; 4]; 4],
+}
+
+impl InstanceData {
+    pub fn new(model: Mat4) -> Self {
+        return InstanceData { model: model.to_cols_array_2d() };
+    }
+}
\ No newline at end of file
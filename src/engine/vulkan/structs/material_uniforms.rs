@@ -0,0 +1,24 @@
+use crate::engine::utils::structs::material::Material;
+
+//Per material data bound at set 1, binding 1 next to the base color texture.
+#[repr(C)]
+#[derive(Default, Copy, Debug, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct MaterialUniforms {
+    base_color: [f32; 4],
+    tint: [f32; 4],
+    //x is the reflectivity, the rest is padding.
+    surface: [f32; 4],
+    //rgb is the emissive color, w how much of the vertex color is emitted on top.
+    emission: [f32; 4],
+}
+
+impl MaterialUniforms {
+    pub fn new(material: &Material) -> Self {
+        return MaterialUniforms {
+            base_color: material.base_color.to_array(),
+            tint: material.tint.extend(1.0).to_array(),
+            surface: [material.reflectivity, 0.0, 0.0, 0.0],
+            emission: material.emissive.extend(material.vertex_color_emission).to_array(),
+        };
+    }
+}
\ No newline at end of file
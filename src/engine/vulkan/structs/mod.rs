@@ -2,4 +2,21 @@ pub mod viewport;
 pub mod vertex;
 pub mod uniform_buffer_object;
 pub mod push_constants;
-pub mod vulkan_object;
\ No newline at end of file
+pub mod vulkan_object;
+pub mod texture;
+pub mod resource_cache;
+pub mod gpu_mesh;
+pub mod instance_data;
+pub mod frame_stats;
+pub mod render_settings;
+pub mod frame_in_flight;
+pub mod material_uniforms;
+pub mod gpu_material;
+pub mod pipeline_description;
+pub mod debug_vertex;
+pub mod text_vertex;
+pub mod gpu_font;
+pub mod ui_vertex;
+pub mod ui_frame;
+pub mod post_process_uniforms;
+pub mod bloom_levels;
\ No newline at end of file
@@ -0,0 +1,265 @@
+use std::path::Path;
+
+use vulkano::pipeline::graphics::{color_blend::{AttachmentBlend, BlendFactor, BlendOp}, input_assembly::PrimitiveTopology, rasterization::{CullMode, PolygonMode}};
+
+//Shaders are compiled from their sources at runtime so they can be hot reloaded. They are found through the crate root, so the engine can be started from any directory.
+pub const SHADER_DIRECTORY: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/shaders");
+
+pub fn shader_path(file_name: &str) -> String {
+    return format!("{}/{}", SHADER_DIRECTORY, file_name);
+}
+
+//What a pipeline reads its vertices from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VertexInput {
+    //Mesh vertices plus the per instance model matrix, the only kind of pipeline materials can pick.
+    Mesh,
+    //Nothing is bound, the vertex shader makes up its vertices from the vertex index.
+    Generated,
+    //Positions and colors of debug lines.
+    DebugLines,
+    //Screen space glyph quads.
+    Text,
+    //Meshes tessellated by the UI.
+    Ui,
+}
+
+//Which render pass a pipeline draws in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PipelinePass {
+    //Into the shadow map from the directional light, depth only.
+    Shadow,
+    //Into the HDR scene target, with depth and multisampling.
+    Scene,
+    //Full screen passes from one HDR target into the other, no depth or multisampling.
+    PostProcess,
+    //Straight onto the presented image once post processing is done.
+    Overlay,
+}
+
+//Everything that sets one named pipeline apart from the others. The pipeline layout and render pass are shared by all of them.
+#[derive(Clone, Debug)]
+pub struct PipelineDescription {
+    pub name: String,
+    pub vertex_shader: String,
+    pub fragment_shader: String,
+    pub vertex_input: VertexInput,
+    pub topology: PrimitiveTopology,
+    pub polygon_mode: PolygonMode,
+    pub cull_mode: CullMode,
+    pub blend: Option<AttachmentBlend>,
+    pub depth_test: bool,
+    pub depth_write: bool,
+    //Transparent pipelines are drawn after all opaque ones, object by object from back to front.
+    pub transparent: bool,
+    pub pass: PipelinePass,
+}
+
+impl PipelineDescription {
+    pub fn opaque() -> Self {
+        return PipelineDescription {
+            name: "opaque".to_string(),
+            vertex_shader: shader_path("shader.vert"),
+            fragment_shader: shader_path("shader.frag"),
+            vertex_input: VertexInput::Mesh,
+            topology: PrimitiveTopology::TriangleList,
+            polygon_mode: PolygonMode::Fill,
+            cull_mode: CullMode::None,
+            blend: None,
+            depth_test: true,
+            depth_write: true,
+            transparent: false,
+            pass: PipelinePass::Scene,
+        };
+    }
+
+    //Lit like opaque, but blended by the alpha of the surface and without writing depth so things behind it stay visible.
+    pub fn alpha_blended() -> Self {
+        return PipelineDescription {
+            name: "alpha_blended".to_string(),
+            blend: Some(AttachmentBlend::alpha()),
+            depth_write: false,
+            transparent: true,
+            ..PipelineDescription::opaque()
+        };
+    }
+
+    //Needs the fill_mode_non_solid device feature.
+    pub fn wireframe() -> Self {
+        return PipelineDescription {
+            name: "wireframe".to_string(),
+            polygon_mode: PolygonMode::Line,
+            ..PipelineDescription::opaque()
+        };
+    }
+
+    //Material color only, lights are ignored.
+    pub fn unlit() -> Self {
+        return PipelineDescription {
+            name: "unlit".to_string(),
+            fragment_shader: shader_path("unlit.frag"),
+            ..PipelineDescription::opaque()
+        };
+    }
+
+    //Draws meshes into the shadow map with the view projection of a cascade, the fragment shader does nothing.
+    pub fn shadow() -> Self {
+        return PipelineDescription {
+            name: "shadow".to_string(),
+            vertex_shader: shader_path("shadow.vert"),
+            fragment_shader: shader_path("shadow.frag"),
+            pass: PipelinePass::Shadow,
+            ..PipelineDescription::opaque()
+        };
+    }
+
+    //Drawn first without touching depth, so everything else ends up in front of it.
+    pub fn skybox() -> Self {
+        return PipelineDescription {
+            name: "skybox".to_string(),
+            vertex_shader: shader_path("skybox.vert"),
+            fragment_shader: shader_path("skybox.frag"),
+            vertex_input: VertexInput::Generated,
+            depth_test: false,
+            depth_write: false,
+            ..PipelineDescription::opaque()
+        };
+    }
+
+    //Hidden by geometry in front of it, but never hides anything itself.
+    pub fn debug_lines() -> Self {
+        return PipelineDescription {
+            name: "debug_lines".to_string(),
+            vertex_shader: shader_path("debug_line.vert"),
+            fragment_shader: shader_path("debug_line.frag"),
+            vertex_input: VertexInput::DebugLines,
+            topology: PrimitiveTopology::LineList,
+            depth_write: false,
+            ..PipelineDescription::opaque()
+        };
+    }
+
+    //Debug lines drawn on top of everything.
+    pub fn debug_lines_overlay() -> Self {
+        return PipelineDescription {
+            name: "debug_lines_overlay".to_string(),
+            depth_test: false,
+            ..PipelineDescription::debug_lines()
+        };
+    }
+
+    //Glyphs blended on top of everything once the scene is done.
+    pub fn text() -> Self {
+        return PipelineDescription {
+            name: "text".to_string(),
+            vertex_shader: shader_path("text.vert"),
+            fragment_shader: shader_path("text.frag"),
+            vertex_input: VertexInput::Text,
+            blend: Some(AttachmentBlend::alpha()),
+            depth_test: false,
+            depth_write: false,
+            pass: PipelinePass::Overlay,
+            ..PipelineDescription::opaque()
+        };
+    }
+
+    //UI colors come premultiplied by alpha.
+    pub fn ui() -> Self {
+        return PipelineDescription {
+            name: "ui".to_string(),
+            vertex_shader: shader_path("ui.vert"),
+            fragment_shader: shader_path("ui.frag"),
+            vertex_input: VertexInput::Ui,
+            blend: Some(AttachmentBlend {
+                src_color_blend_factor: BlendFactor::One,
+                dst_color_blend_factor: BlendFactor::OneMinusSrcAlpha,
+                color_blend_op: BlendOp::Add,
+                src_alpha_blend_factor: BlendFactor::OneMinusDstAlpha,
+                dst_alpha_blend_factor: BlendFactor::One,
+                alpha_blend_op: BlendOp::Add,
+            }),
+            ..PipelineDescription::text()
+        };
+    }
+
+    //One effect of the post processing chain, a full screen triangle running the fragment shader of the same name.
+    pub fn post_process(name: &str) -> Self {
+        return PipelineDescription {
+            name: name.to_string(),
+            vertex_shader: shader_path("fullscreen.vert"),
+            fragment_shader: shader_path(&format!("{}.frag", name)),
+            vertex_input: VertexInput::Generated,
+            depth_test: false,
+            depth_write: false,
+            pass: PipelinePass::PostProcess,
+            ..PipelineDescription::opaque()
+        };
+    }
+
+    //Writes the end of the post processing chain to the presented image, before text and UI go on top.
+    pub fn post_output() -> Self {
+        return PipelineDescription {
+            pass: PipelinePass::Overlay,
+            ..PipelineDescription::post_process("post_output")
+        };
+    }
+
+    pub fn uses_shader(&self, shader_path: &Path) -> bool {
+        return shader_path.ends_with(&self.vertex_shader) || shader_path.ends_with(&self.fragment_shader);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uses_shader_matches_absolute_paths_from_the_watcher() {
+        let unlit = PipelineDescription::unlit();
+
+        assert!(unlit.uses_shader(Path::new(&shader_path("unlit.frag"))));
+        assert!(unlit.uses_shader(Path::new(&shader_path("shader.vert"))));
+        assert!(!unlit.uses_shader(Path::new(&shader_path("shader.frag"))));
+    }
+
+    #[test]
+    fn only_alpha_blended_is_transparent() {
+        let alpha_blended = PipelineDescription::alpha_blended();
+
+        assert!(alpha_blended.transparent && alpha_blended.blend.is_some() && !alpha_blended.depth_write);
+        assert!([PipelineDescription::opaque(), PipelineDescription::wireframe(), PipelineDescription::unlit()].iter().all(|description| !description.transparent && description.depth_write));
+    }
+
+    //Only the scene and shadow passes have a depth attachment, the post processing and overlay passes draw without one.
+    #[test]
+    fn pipelines_outside_the_scene_pass_never_touch_depth() {
+        let descriptions = [PipelineDescription::text(), PipelineDescription::ui(), PipelineDescription::post_process("post_fxaa"), PipelineDescription::post_output()];
+
+        assert!(descriptions.iter().all(|description| description.pass != PipelinePass::Scene && description.pass != PipelinePass::Shadow && !description.depth_test && !description.depth_write));
+    }
+
+    //Drawn on top of everything, but still within the scene pass so the lines are multisampled and post processed like the rest of the scene.
+    #[test]
+    fn debug_lines_overlay_stays_in_the_scene_pass_without_depth() {
+        let overlay = PipelineDescription::debug_lines_overlay();
+
+        assert_eq!(overlay.pass, PipelinePass::Scene);
+        assert!(!overlay.depth_test && !overlay.depth_write);
+    }
+
+    #[test]
+    fn post_output_draws_the_chain_result_in_the_overlay_pass() {
+        let post_output = PipelineDescription::post_output();
+
+        assert_eq!(post_output.pass, PipelinePass::Overlay);
+        assert_eq!(post_output.fragment_shader, shader_path("post_output.frag"));
+    }
+
+    #[test]
+    fn shadow_pipeline_draws_meshes_into_depth() {
+        let shadow = PipelineDescription::shadow();
+
+        assert!(shadow.pass == PipelinePass::Shadow && shadow.vertex_input == VertexInput::Mesh);
+        assert!(shadow.depth_test && shadow.depth_write && shadow.blend.is_none());
+    }
+}
\ No newline at end of file
@@ -0,0 +1,20 @@
+use glam::Vec4;
+
+//Bound at set 2, binding 2 for every post processing pass.
+#[repr(C)]
+#[derive(Default, Copy, Debug, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct PostProcessUniforms {
+    parameters: [f32; 4],
+    //xy is the size of one texel in UV space, zw the size of the image in texels.
+    texel_size: [f32; 4],
+}
+
+impl PostProcessUniforms {
+    pub fn new(parameters: Vec4, extent: [u32; 2]) -> Self {
+        let (width, height) = (extent[0].max(1) as f32, extent[1].max(1) as f32);
+        return PostProcessUniforms {
+            parameters: parameters.to_array(),
+            texel_size: [1.0 / width, 1.0 / height, width, height],
+        };
+    }
+}
\ No newline at end of file
@@ -3,11 +3,11 @@ use glam::Mat4;
 #[repr(C)]
 #[derive(Default, Copy, Debug, Clone, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct PushConstants {
-    mvp: Mat4,
+    view_projection: Mat4,
 }
 
 impl PushConstants {
-    pub fn new(mvp: Mat4) -> Self {
-        return PushConstants { mvp };
+    pub fn new(view_projection: Mat4) -> Self {
+        return PushConstants { view_projection };
     }
 }
\ No newline at end of file
@@ -0,0 +1,64 @@
+use vulkano::image::{SampleCount, SampleCounts};
+
+use crate::engine::utils::logger::{LogLevel, Logger};
+
+//Renderer options that are picked once when the vulkan container is created.
+#[derive(Clone, Debug)]
+pub struct RenderSettings {
+    //How many frames the CPU may record ahead while the GPU is still busy with earlier ones.
+    pub frames_in_flight: usize,
+    //Samples per pixel for anti-aliasing, 1 turns MSAA off. Lowered to what the device supports.
+    pub msaa_samples: u32,
+}
+
+impl RenderSettings {
+    //The highest sample count the device supports that isn't above the requested one.
+    pub fn sample_count(&self, supported_sample_counts: SampleCounts) -> SampleCount {
+        let sample_count = supported_sample_counts.into_iter()
+            .filter(|sample_count| *sample_count as u32 <= self.msaa_samples.max(1))
+            .max_by_key(|sample_count| *sample_count as u32)
+            .unwrap_or(SampleCount::Sample1);
+
+        if sample_count as u32 != self.msaa_samples {
+            Logger::log(LogLevel::High, "vulkan_wrapper", &format!("{} MSAA samples requested, using {} instead.", self.msaa_samples, sample_count as u32));
+        }
+
+        return sample_count;
+    }
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        return RenderSettings {
+            frames_in_flight: 2,
+            msaa_samples: 4,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_samples(msaa_samples: u32) -> RenderSettings {
+        return RenderSettings { msaa_samples, ..Default::default() };
+    }
+
+    #[test]
+    fn sample_count_is_capped_by_the_device() {
+        let supported = SampleCounts::SAMPLE_1 | SampleCounts::SAMPLE_2 | SampleCounts::SAMPLE_4;
+
+        assert_eq!(with_samples(4).sample_count(supported), SampleCount::Sample4);
+        assert_eq!(with_samples(8).sample_count(supported), SampleCount::Sample4);
+        assert_eq!(with_samples(1).sample_count(supported), SampleCount::Sample1);
+    }
+
+    #[test]
+    fn sample_count_rounds_down_to_a_supported_count() {
+        let supported = SampleCounts::SAMPLE_1 | SampleCounts::SAMPLE_4 | SampleCounts::SAMPLE_8;
+
+        assert_eq!(with_samples(2).sample_count(supported), SampleCount::Sample1);
+        assert_eq!(with_samples(6).sample_count(supported), SampleCount::Sample4);
+        assert_eq!(with_samples(0).sample_count(supported), SampleCount::Sample1);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,78 @@
+use std::{collections::HashMap, sync::Arc};
+
+//Reference counted storage for GPU resources that are shared between vulkan objects, keyed by whatever the resource was loaded from.
+pub struct ResourceCache<T> {
+    entries: HashMap<String, (Arc<T>, usize)>,
+}
+
+impl<T> ResourceCache<T> {
+    pub fn new() -> Self {
+        return ResourceCache { entries: HashMap::new() };
+    }
+
+    //Hands out another reference to a cached resource, if it is loaded.
+    pub fn acquire(&mut self, key: &str) -> Option<Arc<T>> {
+        let (resource, references) = self.entries.get_mut(key)?;
+        *references += 1;
+
+        return Some(resource.clone());
+    }
+
+    //Stores a freshly loaded resource, the caller holds the first reference.
+    pub fn insert(&mut self, key: &str, resource: T) -> Arc<T> {
+        let resource = Arc::new(resource);
+        self.entries.insert(key.to_string(), (resource.clone(), 1));
+
+        return resource;
+    }
+
+    //Drops a reference and frees the resource once nobody uses it anymore. Returns true when it was freed.
+    pub fn release(&mut self, key: &str) -> bool {
+        let Some((_, references)) = self.entries.get_mut(key) else {
+            return false;
+        };
+
+        *references -= 1;
+        if *references > 0 {
+            return false;
+        }
+
+        self.entries.remove(key);
+        return true;
+    }
+
+    pub fn len(&self) -> usize {
+        return self.entries.len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ResourceCache;
+
+    #[test]
+    fn resources_are_shared_until_the_last_release() {
+        let mut cache: ResourceCache<u32> = ResourceCache::new();
+
+        assert!(cache.acquire("texture.png").is_none());
+        let first = cache.insert("texture.png", 7);
+        let second = cache.acquire("texture.png").unwrap();
+
+        assert!(std::sync::Arc::ptr_eq(&first, &second));
+        assert_eq!(cache.len(), 1);
+
+        assert!(!cache.release("texture.png"));
+        assert_eq!(cache.len(), 1);
+        assert!(cache.release("texture.png"));
+        assert_eq!(cache.len(), 0);
+    }
+
+    #[test]
+    fn releasing_unknown_keys_does_nothing() {
+        let mut cache: ResourceCache<u32> = ResourceCache::new();
+        cache.insert("a", 1);
+
+        assert!(!cache.release("b"));
+        assert_eq!(cache.len(), 1);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,19 @@
+use glam::{Vec2, Vec4};
+
+//One corner of a glyph quad. Positions are already in normalized device coordinates since text is never transformed.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, bytemuck::Pod, bytemuck::Zeroable, vulkano::pipeline::graphics::vertex_input::Vertex)]
+pub struct TextVertex {
+    #[format(R32G32_SFLOAT)]
+    position: Vec2,
+    #[format(R32G32_SFLOAT)]
+    tex_coord: Vec2,
+    #[format(R32G32B32A32_SFLOAT)]
+    color: Vec4,
+}
+
+impl TextVertex {
+    pub fn new(position: Vec2, tex_coord: Vec2, color: Vec4) -> Self {
+        return TextVertex { position, tex_coord, color };
+    }
+}
\ No newline at end of file
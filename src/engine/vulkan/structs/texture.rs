@@ -0,0 +1,24 @@
+use std::sync::Arc;
+
+use vulkano::image::view::ImageView;
+
+//Texture image shared by every material using the same file. Samplers belong to the materials since they pick the filtering.
+#[derive(Debug)]
+pub struct Texture {
+    path: String,
+    image_view: Arc<ImageView>,
+}
+
+impl Texture {
+    pub fn new(path: &str, image_view: Arc<ImageView>) -> Self {
+        return Texture { path: path.to_string(), image_view };
+    }
+
+    pub fn get_path(&self) -> &str {
+        return &self.path;
+    }
+
+    pub fn get_image_view(&self) -> Arc<ImageView> {
+        return self.image_view.clone();
+    }
+}
\ No newline at end of file
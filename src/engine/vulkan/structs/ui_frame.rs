@@ -0,0 +1,9 @@
+use egui::{ClippedPrimitive, TexturesDelta};
+
+//Everything the UI wants drawn this frame, tessellated and ready to paint.
+#[derive(Default)]
+pub struct UiFrame {
+    pub textures_delta: TexturesDelta,
+    pub primitives: Vec<ClippedPrimitive>,
+    pub pixels_per_point: f32,
+}
\ No newline at end of file
@@ -0,0 +1,19 @@
+use glam::Vec2;
+
+//A vertex of a UI mesh as egui tessellates it. Positions are in points and the color is premultiplied sRGB.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, bytemuck::Pod, bytemuck::Zeroable, vulkano::pipeline::graphics::vertex_input::Vertex)]
+pub struct UiVertex {
+    #[format(R32G32_SFLOAT)]
+    position: Vec2,
+    #[format(R32G32_SFLOAT)]
+    tex_coord: Vec2,
+    #[format(R8G8B8A8_UNORM)]
+    color: [u8; 4],
+}
+
+impl UiVertex {
+    pub fn new(position: Vec2, tex_coord: Vec2, color: [u8; 4]) -> Self {
+        return UiVertex { position, tex_coord, color };
+    }
+}
\ No newline at end of file
@@ -1,5 +1,97 @@
+use glam::{Mat4, Vec3};
+
+use crate::engine::utils::structs::{light::{DirectionalLight, PointLight}, shadow::{ShadowSettings, MAX_SHADOW_CASCADES}};
+
+//Has to match MAX_POINT_LIGHTS in shader.frag.
+pub const MAX_POINT_LIGHTS: usize = 8;
+
+#[repr(C)]
+#[derive(Default, Copy, Debug, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct PointLightData {
+    position_range: [f32; 4],
+    color_intensity: [f32; 4],
+}
+
+//Per frame data bound at set 0, binding 0. Everything is packed into vec4s so the layout is the same under std140.
 #[repr(C)]
 #[derive(Default, Copy, Debug, Clone, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct UniformBufferObject {
-    pub mvp: [[f32; 4]; 4],
+    ambient_color: [f32; 4],
+    camera_position: [f32; 4],
+    light_direction: [f32; 4],
+    light_color_intensity: [f32; 4],
+    point_lights: [PointLightData; MAX_POINT_LIGHTS],
+    point_light_count: [u32; 4],
+    shadow_matrices: [[f32; 16]; MAX_SHADOW_CASCADES],
+    //x is the bias, y the amount of cascades and zw the size of a shadow map texel in texture coordinates.
+    shadow_parameters: [f32; 4],
+}
+
+impl UniformBufferObject {
+    //Only the first MAX_POINT_LIGHTS point lights make it into the buffer, the rest are ignored.
+    pub fn new<'a>(ambient_color: Vec3, camera_position: Vec3, directional_light: &DirectionalLight, point_lights: impl Iterator<Item = &'a PointLight>) -> Self {
+        let mut uniform_buffer_object = UniformBufferObject {
+            ambient_color: ambient_color.extend(1.0).to_array(),
+            camera_position: camera_position.extend(1.0).to_array(),
+            light_direction: directional_light.direction.extend(0.0).to_array(),
+            light_color_intensity: directional_light.color.extend(directional_light.intensity).to_array(),
+            ..Default::default()
+        };
+
+        let mut point_light_count = 0;
+        for point_light in point_lights.take(MAX_POINT_LIGHTS) {
+            uniform_buffer_object.point_lights[point_light_count] = PointLightData {
+                position_range: point_light.position.extend(point_light.range).to_array(),
+                color_intensity: point_light.color.extend(point_light.intensity).to_array(),
+            };
+            point_light_count += 1;
+        }
+        uniform_buffer_object.point_light_count[0] = point_light_count as u32;
+
+        return uniform_buffer_object;
+    }
+
+    //One matrix per cascade, cascades past MAX_SHADOW_CASCADES are ignored. Cascades sit side by side in the shadow map.
+    pub fn with_shadows(mut self, shadow_matrices: &[Mat4], shadow_settings: &ShadowSettings) -> Self {
+        let cascades = shadow_matrices.len().min(MAX_SHADOW_CASCADES);
+        for (cascade, shadow_matrix) in shadow_matrices.iter().take(cascades).enumerate() {
+            self.shadow_matrices[cascade] = shadow_matrix.to_cols_array();
+        }
+        self.shadow_parameters = [shadow_settings.bias, cascades as f32, 1.0 / (shadow_settings.resolution as f32 * cascades as f32), 1.0 / shadow_settings.resolution as f32];
+
+        return self;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{vec3, Vec3};
+
+    use super::*;
+
+    #[test]
+    fn size_is_a_multiple_of_a_vec4() {
+        assert_eq!(std::mem::size_of::<UniformBufferObject>() % 16, 0);
+        assert_eq!(std::mem::size_of::<UniformBufferObject>(), 16 * (6 + 2 * MAX_POINT_LIGHTS + 4 * MAX_SHADOW_CASCADES));
+    }
+
+    #[test]
+    fn packs_lights_and_caps_point_lights() {
+        let point_lights: Vec<PointLight> = (0..MAX_POINT_LIGHTS + 3).map(|index| PointLight::new(vec3(index as f32, 0.0, 0.0), Vec3::ONE, 2.0, 5.0)).collect();
+        let uniform_buffer_object = UniformBufferObject::new(Vec3::splat(0.1), Vec3::ZERO, &DirectionalLight::new(vec3(0.0, -2.0, 0.0), Vec3::ONE, 1.0), point_lights.iter());
+
+        assert_eq!(uniform_buffer_object.point_light_count[0] as usize, MAX_POINT_LIGHTS);
+        assert_eq!(uniform_buffer_object.light_direction, [0.0, -1.0, 0.0, 0.0]);
+        assert_eq!(uniform_buffer_object.point_lights[3].position_range, [3.0, 0.0, 0.0, 5.0]);
+        assert_eq!(uniform_buffer_object.point_lights[3].color_intensity, [1.0, 1.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn shadow_parameters_describe_the_cascades_side_by_side() {
+        let shadow_matrices = [Mat4::IDENTITY, Mat4::from_scale(Vec3::splat(2.0))];
+        let uniform_buffer_object = UniformBufferObject::new(Vec3::ZERO, Vec3::ZERO, &DirectionalLight::default(), [].iter()).with_shadows(&shadow_matrices, &ShadowSettings::new(512, 0.1, 2, 30.0));
+
+        assert_eq!(uniform_buffer_object.shadow_matrices[1], shadow_matrices[1].to_cols_array());
+        assert_eq!(uniform_buffer_object.shadow_parameters, [0.1, 2.0, 1.0 / 1024.0, 1.0 / 512.0]);
+    }
 }
\ No newline at end of file
@@ -9,10 +9,20 @@ pub struct Vertex {
     color: Vec3,
     #[format(R32G32_SFLOAT)]
     texCoord: Vec2,
+    #[format(R32G32B32_SFLOAT)]
+    normal: Vec3,
 }
 
 impl Vertex {
-    pub fn new(position: Vec3, color: Vec3, tex_coord: Vec2) -> Self {
-        Self { position, color, texCoord: tex_coord}
+    pub fn new(position: Vec3, color: Vec3, tex_coord: Vec2, normal: Vec3) -> Self {
+        Self { position, color, texCoord: tex_coord, normal }
+    }
+
+    pub fn get_position(&self) -> Vec3 {
+        return self.position;
+    }
+
+    pub fn get_normal(&self) -> Vec3 {
+        return self.normal;
     }
 }
\ No newline at end of file
@@ -1,30 +1,41 @@
 use std::sync::Arc;
 
-use vulkano::{buffer::Subbuffer, descriptor_set::DescriptorSet};
-
-use crate::engine::{utils::structs::transform::Transform, vulkan::structs::vertex::Vertex};
+use crate::engine::{utils::structs::{bounding_sphere::BoundingSphere, material::MaterialHandle, transform::Transform}, vulkan::structs::gpu_mesh::GpuMesh};
 
 #[derive(Debug)]
 pub struct VulkanObject {
-    vertex_buffer: Subbuffer<[Vertex]>,
+    mesh: Arc<GpuMesh>,
     object_transform: Transform,
-    texture_descriptor_set: Arc<DescriptorSet>
+    //Resolved every frame, so replacing a material shows up on every object using it.
+    material: MaterialHandle,
+    //World space bounds, kept up to date with the transform so culling doesn't have to recompute them every frame.
+    bounds: BoundingSphere,
 }
 
 impl VulkanObject {
-    pub fn new(vertex_buffer: vulkano::buffer::Subbuffer<[Vertex]>, object_transform: Transform, texture_descriptor_set: Arc<DescriptorSet>) -> Self {
-        return VulkanObject { vertex_buffer, object_transform, texture_descriptor_set}
+    pub fn new(mesh: Arc<GpuMesh>, object_transform: Transform, material: MaterialHandle) -> Self {
+        let bounds = mesh.get_bounds().transformed(&object_transform);
+        return VulkanObject { mesh, object_transform, material, bounds}
     }
 
     pub fn get_transform(&self) -> &Transform {
         return &self.object_transform;
     }
 
-    pub fn get_buffer(&self) -> &vulkano::buffer::Subbuffer<[Vertex]> {
-        return &self.vertex_buffer;
+    pub fn set_transform(&mut self, object_transform: Transform) {
+        self.bounds = self.mesh.get_bounds().transformed(&object_transform);
+        self.object_transform = object_transform;
+    }
+
+    pub fn get_bounds(&self) -> &BoundingSphere {
+        return &self.bounds;
+    }
+
+    pub fn get_mesh(&self) -> &Arc<GpuMesh> {
+        return &self.mesh;
     }
 
-    pub fn get_descriptor_set(&self) -> Arc<DescriptorSet> {
-        return self.texture_descriptor_set.clone();
+    pub fn get_material(&self) -> &MaterialHandle {
+        return &self.material;
     }
 }
\ No newline at end of file
@@ -0,0 +1,202 @@
+use std::{collections::HashMap, sync::Arc};
+
+use egui::{epaint::{ImageDelta, Primitive}, ClippedPrimitive, Color32, ImageData, Rect, TextureFilter, TextureId, TextureOptions, TextureWrapMode};
+use glam::vec2;
+use vulkano::{descriptor_set::DescriptorSet, image::sampler::{Filter, SamplerAddressMode, SamplerCreateInfo}, pipeline::graphics::viewport::Scissor};
+
+use crate::engine::vulkan::structs::ui_vertex::UiVertex;
+
+//A UI texture as egui last described it. The pixels stay on the CPU so partial updates can be patched in and the whole texture uploaded again.
+struct UiTexture {
+    size: [usize; 2],
+    pixels: Vec<Color32>,
+    options: TextureOptions,
+    descriptor_set: Option<Arc<DescriptorSet>>,
+}
+
+//Every texture egui asked for, keyed by its id.
+pub struct UiTextureStore {
+    textures: HashMap<TextureId, UiTexture>,
+}
+
+impl UiTextureStore {
+    pub fn new() -> Self {
+        return UiTextureStore { textures: HashMap::new() };
+    }
+
+    //Applies a new or changed texture, the descriptor set is dropped until the container uploads the texture again.
+    pub fn apply(&mut self, id: TextureId, delta: &ImageDelta) {
+        let [width, height] = delta.image.size();
+        let pixels: Vec<Color32> = match &delta.image {
+            ImageData::Color(image) => image.pixels.clone(),
+            ImageData::Font(image) => image.srgba_pixels(None).collect(),
+        };
+
+        let Some(position) = delta.pos else {
+            self.textures.insert(id, UiTexture { size: [width, height], pixels, options: delta.options, descriptor_set: None });
+            return;
+        };
+
+        let Some(texture) = self.textures.get_mut(&id) else {
+            return;
+        };
+        for row in 0..height {
+            let destination = (position[1] + row) * texture.size[0] + position[0];
+            texture.pixels[destination..destination + width].copy_from_slice(&pixels[row * width..(row + 1) * width]);
+        }
+        texture.descriptor_set = None;
+    }
+
+    pub fn free(&mut self, id: TextureId) {
+        self.textures.remove(&id);
+    }
+
+    //Textures that changed since they were last uploaded.
+    pub fn pending_uploads(&self) -> Vec<TextureId> {
+        return self.textures.iter().filter(|(_, texture)| texture.descriptor_set.is_none()).map(|(id, _)| *id).collect();
+    }
+
+    //Width, height and RGBA bytes of a texture.
+    pub fn get_rgba(&self, id: TextureId) -> Option<(u32, u32, Vec<u8>)> {
+        let texture = self.textures.get(&id)?;
+        return Some((texture.size[0] as u32, texture.size[1] as u32, texture.pixels.iter().flat_map(|pixel| pixel.to_array()).collect()));
+    }
+
+    pub fn get_sampler_create_info(&self, id: TextureId) -> Option<SamplerCreateInfo> {
+        let options = self.textures.get(&id)?.options;
+        let filter = |filter: TextureFilter| match filter {
+            TextureFilter::Nearest => Filter::Nearest,
+            TextureFilter::Linear => Filter::Linear,
+        };
+        let address_mode = match options.wrap_mode {
+            TextureWrapMode::ClampToEdge => SamplerAddressMode::ClampToEdge,
+            TextureWrapMode::Repeat => SamplerAddressMode::Repeat,
+            TextureWrapMode::MirroredRepeat => SamplerAddressMode::MirroredRepeat,
+        };
+
+        return Some(SamplerCreateInfo {
+            mag_filter: filter(options.magnification),
+            min_filter: filter(options.minification),
+            address_mode: [address_mode; 3],
+            ..Default::default()
+        });
+    }
+
+    pub fn set_descriptor_set(&mut self, id: TextureId, descriptor_set: Arc<DescriptorSet>) {
+        if let Some(texture) = self.textures.get_mut(&id) {
+            texture.descriptor_set = Some(descriptor_set);
+        }
+    }
+
+    pub fn get_descriptor_set(&self, id: TextureId) -> Option<Arc<DescriptorSet>> {
+        return self.textures.get(&id)?.descriptor_set.clone();
+    }
+}
+
+//One mesh of the UI, its indices and vertices sit in buffers shared by the whole UI at these offsets.
+pub struct UiDraw {
+    pub texture: TextureId,
+    pub scissor: Scissor,
+    pub first_index: u32,
+    pub index_count: u32,
+    pub vertex_offset: i32,
+}
+
+//Flattens the tessellated UI into one vertex and one index buffer worth of data. Meshes clipped away entirely are left out.
+pub fn build_ui_draws(primitives: &[ClippedPrimitive], pixels_per_point: f32, extent: [u32; 2]) -> (Vec<UiVertex>, Vec<u32>, Vec<UiDraw>) {
+    let mut vertices = vec![];
+    let mut indices = vec![];
+    let mut draws = vec![];
+
+    for primitive in primitives.iter() {
+        //Paint callbacks are for custom rendering inside the UI, which nothing here uses.
+        let Primitive::Mesh(mesh) = &primitive.primitive else {
+            continue;
+        };
+        let Some(scissor) = clip_rect_to_scissor(primitive.clip_rect, pixels_per_point, extent) else {
+            continue;
+        };
+        if mesh.indices.is_empty() {
+            continue;
+        }
+
+        draws.push(UiDraw {
+            texture: mesh.texture_id,
+            scissor,
+            first_index: indices.len() as u32,
+            index_count: mesh.indices.len() as u32,
+            vertex_offset: vertices.len() as i32,
+        });
+        indices.extend_from_slice(&mesh.indices);
+        vertices.extend(mesh.vertices.iter().map(|vertex| UiVertex::new(vec2(vertex.pos.x, vertex.pos.y), vec2(vertex.uv.x, vertex.uv.y), vertex.color.to_array())));
+    }
+
+    return (vertices, indices, draws);
+}
+
+//Clip rectangles are in points, scissors in framebuffer pixels and they may not reach outside of it.
+pub fn clip_rect_to_scissor(clip_rect: Rect, pixels_per_point: f32, extent: [u32; 2]) -> Option<Scissor> {
+    let min_x = (clip_rect.min.x * pixels_per_point).round().clamp(0.0, extent[0] as f32) as u32;
+    let min_y = (clip_rect.min.y * pixels_per_point).round().clamp(0.0, extent[1] as f32) as u32;
+    let max_x = (clip_rect.max.x * pixels_per_point).round().clamp(0.0, extent[0] as f32) as u32;
+    let max_y = (clip_rect.max.y * pixels_per_point).round().clamp(0.0, extent[1] as f32) as u32;
+
+    if max_x <= min_x || max_y <= min_y {
+        return None;
+    }
+
+    return Some(Scissor { offset: [min_x, min_y], extent: [max_x - min_x, max_y - min_y] });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use egui::{epaint::Mesh, pos2, ColorImage};
+
+    #[test]
+    fn clip_rects_are_scaled_and_clamped_to_the_framebuffer() {
+        let scissor = clip_rect_to_scissor(Rect::from_min_max(pos2(-10.0, 5.0), pos2(100.0, 400.0)), 2.0, [150, 300]).unwrap();
+
+        assert_eq!((scissor.offset, scissor.extent), ([0, 10], [150, 290]));
+        assert!(clip_rect_to_scissor(Rect::from_min_max(pos2(200.0, 0.0), pos2(300.0, 10.0)), 1.0, [150, 300]).is_none());
+    }
+
+    #[test]
+    fn partial_updates_are_patched_into_the_full_texture() {
+        let mut store = UiTextureStore::new();
+        let id = TextureId::Managed(0);
+        store.apply(id, &ImageDelta::full(ColorImage::new([4, 4], Color32::BLACK), TextureOptions::LINEAR));
+        store.apply(id, &ImageDelta::partial([1, 2], ColorImage::new([2, 1], Color32::WHITE), TextureOptions::LINEAR));
+
+        let (width, height, rgba) = store.get_rgba(id).unwrap();
+        assert_eq!((width, height), (4, 4));
+        let white_pixels: Vec<usize> = rgba.chunks(4).enumerate().filter(|(_, pixel)| pixel[0] == 255).map(|(index, _)| index).collect();
+        assert_eq!(white_pixels, vec![9, 10]);
+        assert_eq!(store.pending_uploads(), vec![id]);
+
+        store.free(id);
+        assert!(store.get_rgba(id).is_none());
+    }
+
+    #[test]
+    fn meshes_share_buffers_at_their_own_offsets() {
+        let mut first = Mesh::default();
+        first.add_colored_rect(Rect::from_min_max(pos2(0.0, 0.0), pos2(10.0, 10.0)), Color32::RED);
+        let mut second = Mesh::with_texture(TextureId::User(3));
+        second.add_rect_with_uv(Rect::from_min_max(pos2(20.0, 0.0), pos2(30.0, 10.0)), Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0)), Color32::BLUE);
+        let clipped_away = ClippedPrimitive { clip_rect: Rect::from_min_max(pos2(500.0, 500.0), pos2(600.0, 600.0)), primitive: Primitive::Mesh(first.clone()) };
+        let screen = Rect::from_min_max(pos2(0.0, 0.0), pos2(100.0, 100.0));
+
+        let primitives = vec![
+            ClippedPrimitive { clip_rect: screen, primitive: Primitive::Mesh(first) },
+            clipped_away,
+            ClippedPrimitive { clip_rect: screen, primitive: Primitive::Mesh(second) },
+        ];
+        let (vertices, indices, draws) = build_ui_draws(&primitives, 1.0, [100, 100]);
+
+        assert_eq!((vertices.len(), indices.len(), draws.len()), (8, 12, 2));
+        assert_eq!((draws[1].first_index, draws[1].index_count, draws[1].vertex_offset), (6, 6, 4));
+        assert_eq!(draws[1].texture, TextureId::User(3));
+    }
+}
\ No newline at end of file
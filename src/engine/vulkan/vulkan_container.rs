@@ -1,38 +1,112 @@
-use std::{collections::{BTreeMap, HashMap, HashSet}, fs::File, io::Read, ops::Range, sync::Arc, vec};
-use glam::{Mat4, Vec3};
-use vulkano::{self, buffer::{Buffer, BufferCreateInfo, BufferUsage}, command_buffer::{allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder, CommandBufferUsage, CopyBufferToImageInfo, PrimaryAutoCommandBuffer, RenderPassBeginInfo, SubpassBeginInfo, SubpassContents, SubpassEndInfo}, descriptor_set::{self, allocator::{DescriptorSetAllocator, StandardDescriptorSetAllocator, StandardDescriptorSetAllocatorCreateInfo}, layout::{self, DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorSetLayoutCreateInfo, DescriptorType}, DescriptorSet, WriteDescriptorSet}, device::{physical::{PhysicalDevice, PhysicalDeviceType}, Device, DeviceExtensions, Queue}, format::{ClearValue, Format}, image::{sampler::{ComponentMapping, ComponentSwizzle, Filter, Sampler, SamplerAddressMode, SamplerCreateInfo}, view::{ImageView, ImageViewCreateInfo, ImageViewType}, Image, ImageAspect, ImageCreateInfo, ImageSubresourceRange, ImageType, ImageUsage}, instance::Instance, memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator}, pipeline::{graphics::{color_blend::{ColorBlendAttachmentState, ColorBlendState, ColorComponents}, depth_stencil::{DepthState, DepthStencilState}, input_assembly::InputAssemblyState, multisample::MultisampleState, rasterization::RasterizationState, vertex_input::VertexDefinition, viewport::{Scissor, Viewport, ViewportState}, GraphicsPipelineCreateInfo}, layout::{PipelineLayoutCreateInfo, PushConstantRange}, DynamicState, GraphicsPipeline, Pipeline, PipelineLayout, PipelineShaderStageCreateInfo}, render_pass::{Framebuffer, RenderPass, Subpass}, shader::{self, ShaderModule, ShaderModuleCreateInfo, ShaderStages}, swapchain::{self, Surface, Swapchain, SwapchainCreateInfo, SwapchainPresentInfo}, sync::{self, GpuFuture}};
+use std::{collections::{BTreeMap, HashMap, HashSet}, ops::Range, sync::Arc, time::Instant, vec};
+use glam::{vec2, Mat4, Vec2, Vec3, Vec4};
+use half::f16;
+use vulkano::{self, buffer::{allocator::{SubbufferAllocator, SubbufferAllocatorCreateInfo}, Buffer, BufferCreateInfo, BufferUsage}, command_buffer::{allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder, CommandBufferUsage, CopyBufferToImageInfo, CopyImageToBufferInfo, PrimaryAutoCommandBuffer, SubpassEndInfo}, descriptor_set::{self, allocator::{DescriptorSetAllocator, StandardDescriptorSetAllocator, StandardDescriptorSetAllocatorCreateInfo}, layout::{self, DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorSetLayoutCreateInfo, DescriptorType}, DescriptorSet, WriteDescriptorSet}, device::{physical::{PhysicalDevice, PhysicalDeviceType}, Device, DeviceExtensions, DeviceFeatures, Queue}, format::{ClearValue, Format}, image::{sampler::{ComponentMapping, ComponentSwizzle, Filter, Sampler, SamplerAddressMode, SamplerCreateInfo}, view::{ImageView, ImageViewCreateInfo, ImageViewType}, Image, ImageAspect, ImageCreateFlags, ImageCreateInfo, ImageSubresourceRange, ImageType, ImageUsage, SampleCount}, instance::{Instance, InstanceExtensions}, memory::allocator::{AllocationCreateInfo, MemoryTypeFilter, StandardMemoryAllocator}, pipeline::{graphics::{color_blend::{ColorBlendAttachmentState, ColorBlendState, ColorComponents}, depth_stencil::{CompareOp, DepthState, DepthStencilState}, input_assembly::InputAssemblyState, multisample::MultisampleState, rasterization::{PolygonMode, RasterizationState}, vertex_input::{VertexDefinition, VertexInputState}, viewport::{Scissor, Viewport, ViewportState}, GraphicsPipelineCreateInfo}, layout::{PipelineLayoutCreateInfo, PushConstantRange}, cache::{PipelineCache, PipelineCacheCreateInfo}, DynamicState, GraphicsPipeline, PipelineLayout, PipelineShaderStageCreateInfo}, render_pass::{RenderPass, Subpass}, shader::{ShaderModule, ShaderModuleCreateInfo, ShaderStages}, swapchain::{self, Surface, Swapchain, SwapchainCreateInfo, SwapchainPresentInfo}, sync::{self, GpuFuture}, Validated, VulkanError};
 use winit::{event_loop::{ActiveEventLoop}, window::{Window}};
 use smallvec::{smallvec, SmallVec};
 use std::path::Path;
 
-use crate::engine::{utils::{logger::{LogLevel, Logger}, structs::transform::Transform}, vulkan::structs::{push_constants::PushConstants, vertex::Vertex, vulkan_object::VulkanObject}};
+use crate::engine::{utils::{debug_draw::{take_debug_lines, DebugLine}, logger::{LogLevel, Logger}, structs::{bounding_sphere::BoundingSphere, frustum::Frustum, light::{DirectionalLight, PointLight}, material::{Material, MaterialHandle}, mesh::Mesh, post_process::{PostProcessChain, PostProcessEffect}, shadow::{cascade_view_projections, frustum_corners, ShadowSettings}, skybox::{CubemapPixels, SkyboxSource}, text::TextSection, transform::Transform}}, vulkan::structs::{bloom_levels::bloom_level_extents, debug_vertex::DebugVertex, gpu_font::GpuFont, frame_in_flight::FrameInFlight, frame_stats::FrameStats, render_settings::RenderSettings, gpu_material::GpuMaterial, pipeline_description::{PipelineDescription, PipelinePass, VertexInput, SHADER_DIRECTORY}, post_process_uniforms::PostProcessUniforms, gpu_mesh::GpuMesh, instance_data::InstanceData, material_uniforms::MaterialUniforms, push_constants::PushConstants, resource_cache::ResourceCache, text_vertex::TextVertex, texture::Texture, ui_frame::UiFrame, ui_vertex::UiVertex, uniform_buffer_object::{UniformBufferObject, MAX_POINT_LIGHTS}, vulkan_object::VulkanObject}};
 use crate::engine::vulkan::structs::viewport::ViewportInfo;
 use crate::engine::vulkan::structs::vertex;
+use crate::engine::vulkan::{glyph_atlas::GlyphAtlas, pipeline_cache_file::PipelineCacheFile, pipeline_registry::{PipelineRegistry, DEFAULT_PIPELINE}, render_graph::{AttachmentDeclaration, AttachmentLoad, AttachmentSize, PassDeclaration, RenderGraph, RenderGraphDescription, PRESENT_ATTACHMENT}, shader_compiler::ShaderCompiler, shader_watcher::ShaderWatcher, ui_painter::{build_ui_draws, UiTextureStore}};
 use crate::engine::vulkan::vulkan_container::vulkano::pipeline::PipelineBindPoint;
 
+//Headless containers have no window, surface or swapchain and render into a single offscreen image instead.
+const OFFSCREEN_FORMAT: Format = Format::R8G8B8A8_SRGB;
+//The scene is drawn into half float targets so lights can go past 1 until post processing maps them back into range.
+const HDR_FORMAT: Format = Format::R16G16B16A16_SFLOAT;
+//The scene is drawn into the first HDR target and post processing bounces between both, with a pass drawing into each.
+const HDR_TARGETS: [&str; 2] = ["hdr_0", "hdr_1"];
+const POST_PASSES: [&str; 2] = ["post_0", "post_1"];
+//A single depth image holds the shadow maps of all cascades, side by side.
+const SHADOW_FORMAT: Format = Format::D16_UNORM;
+
+const CAMERA_FOV_Y_DEGREES: f32 = 45.0;
+const CAMERA_NEAR: f32 = 0.1;
+const CAMERA_FAR: f32 = 1000.0;
+
+//Shipped with the engine and used by the default material.
+const DEFAULT_TEXTURE_PATH: &str = "src/engine/vulkan/base_resources/default_texture.png";
+//Cache key of the single white pixel texture used by materials without a base color texture.
+const WHITE_TEXTURE_KEY: &str = "<white>";
+//Font text is drawn with until another one is set, baked at this size in pixels.
+const DEFAULT_FONT_PATH: &str = "src/engine/vulkan/base_resources/DejaVuSansMono.ttf";
+const DEFAULT_FONT_SIZE: f32 = 20.0;
+
+struct DrawBatch<'a> {
+    pipeline: &'a str,
+    mesh: &'a Arc<GpuMesh>,
+    material: &'a Arc<GpuMaterial>,
+    //World space bounds of every instance, so the batch can be culled against the camera and every shadow cascade without being built again.
+    bounds: Vec<BoundingSphere>,
+    instances: Vec<InstanceData>,
+}
+
+impl DrawBatch<'_> {
+    //Appends the instances inside the frustum to visible and returns how many there were.
+    fn cull_into(&self, frustum: &Frustum, visible: &mut Vec<InstanceData>) -> usize {
+        let visible_before = visible.len();
+        visible.extend(self.bounds.iter().zip(self.instances.iter()).filter(|(bounds, _)| frustum.intersects_sphere(bounds)).map(|(_, instance)| *instance));
+        return visible.len() - visible_before;
+    }
+}
+
 pub struct VulkanContainer {
     instance: Arc<Instance>,
-    surface: Arc<Surface>,
+    surface: Option<Arc<Surface>>,
     physical_device: Arc<PhysicalDevice>,
     logical_device: Arc<Device>,
-    window: Arc<Window>,
+    window: Option<Arc<Window>>,
     queue: Arc<Queue>,
-    swapchain: Arc<Swapchain>,
+    swapchain: Option<Arc<Swapchain>>,
     images: Vec<Arc<Image>>,
     image_views: Vec<Arc<ImageView>>,
-    render_pass: Arc<RenderPass>,
+    render_graph: RenderGraph,
+    sample_count: SampleCount,
     memory_allocator: Arc<StandardMemoryAllocator>,
     command_buffer_allocator: Arc<StandardCommandBufferAllocator>,
     descriptor_set_allocator: Arc<StandardDescriptorSetAllocator>,
-    graphics_pipeline: Arc<GraphicsPipeline>,
-    framebuffers: Vec<Arc<Framebuffer>>,
+    pipeline_layout: Arc<PipelineLayout>,
+    pipeline_cache: Arc<PipelineCache>,
+    pipeline_cache_file: Option<PipelineCacheFile>,
+    pipelines: PipelineRegistry,
+    shader_compiler: ShaderCompiler,
+    shader_watcher: Option<ShaderWatcher>,
     viewports: SmallVec<[Viewport; 2]>,
     scissors: SmallVec<[Scissor; 2]>,
     vulkan_objects: HashMap<usize, VulkanObject>,
+    texture_cache: ResourceCache<Texture>,
+    materials: HashMap<MaterialHandle, Arc<GpuMaterial>>,
+    mesh_cache: ResourceCache<GpuMesh>,
+    instance_buffer_allocator: SubbufferAllocator,
+    uniform_buffer_allocator: SubbufferAllocator,
+    ambient_light: Vec3,
+    directional_light: DirectionalLight,
+    point_lights: BTreeMap<usize, PointLight>,
+    environment_map: Arc<ImageView>,
+    environment_sampler: Arc<Sampler>,
+    skybox_enabled: bool,
+    post_process_chain: PostProcessChain,
+    post_process_luts: HashMap<String, Arc<Texture>>,
+    post_sampler: Arc<Sampler>,
+    shadow_settings: ShadowSettings,
+    shadow_sampler: Arc<Sampler>,
+    font: Option<GpuFont>,
+    text_sections: Vec<TextSection>,
+    ui_textures: UiTextureStore,
+    ui_frame: UiFrame,
+    instancing: bool,
+    frame_stats: FrameStats,
+    frames_in_flight: Vec<FrameInFlight>,
+    current_frame: usize,
+    previous_frame: usize,
+    swapchain_outdated: bool,
+    offscreen_frame_error: Option<String>,
 }
 
 impl VulkanContainer {
-    pub fn new(event_loop: &ActiveEventLoop, window: Arc<Window>, viewport_info: &ViewportInfo) -> Self {
+    pub fn new(event_loop: &ActiveEventLoop, window: Arc<Window>, viewport_info: &ViewportInfo, render_settings: RenderSettings) -> Self {
         Logger::log(LogLevel::High, "vulkan_wrapper", "Creating Vulkan wrapper...");
 
         let device_extensions = DeviceExtensions {
@@ -40,18 +114,87 @@ impl VulkanContainer {
             ..Default::default()
         };
         
-        let instance = VulkanContainer::create_instance(event_loop);
+        let required_extensions = Surface::required_extensions(&event_loop).unwrap();
+        let instance = VulkanContainer::create_instance(required_extensions);
         let surface = VulkanContainer::create_surface(&instance, window.clone());
-        let (physical_device, queue_family_index) = VulkanContainer::create_physical_device(&instance, &surface, &device_extensions);
+        let (physical_device, queue_family_index) = VulkanContainer::create_physical_device(&instance, Some(&surface), &device_extensions);
         let (logical_device, queue) = VulkanContainer::create_logical_device(physical_device.clone(), queue_family_index, &device_extensions);
         let (swapchain, images) = VulkanContainer::create_swapchain(physical_device.clone(), logical_device.clone(), window.clone(), surface.clone());
-        let image_views = VulkanContainer::create_image_views(&images);
-        let render_pass = VulkanContainer::create_render_pass(logical_device.clone(), swapchain.clone());
         let memory_allocator = VulkanContainer::create_memory_allocator(logical_device.clone());
+
+        let vulkan_wrapper = VulkanContainer::from_parts(instance, Some(surface), physical_device, logical_device, Some(window), queue, Some(swapchain), images, memory_allocator, viewport_info, render_settings);
+
+        Logger::log(LogLevel::High, "vulkan_wrapper", "Vulkan wrapper created successfully.");
+        return vulkan_wrapper;
+    }
+
+    pub fn new_headless(viewport_info: &ViewportInfo, render_settings: RenderSettings) -> Self {
+        Logger::log(LogLevel::High, "vulkan_wrapper", "Creating headless Vulkan wrapper...");
+
+        let device_extensions = DeviceExtensions::default();
+
+        let instance = VulkanContainer::create_instance(InstanceExtensions::empty());
+        let (physical_device, queue_family_index) = VulkanContainer::create_physical_device(&instance, None, &device_extensions);
+        let (logical_device, queue) = VulkanContainer::create_logical_device(physical_device.clone(), queue_family_index, &device_extensions);
+        let memory_allocator = VulkanContainer::create_memory_allocator(logical_device.clone());
+        let images = vec![VulkanContainer::create_offscreen_image(memory_allocator.clone(), viewport_info)];
+
+        let vulkan_wrapper = VulkanContainer::from_parts(instance, None, physical_device, logical_device, None, queue, None, images, memory_allocator, viewport_info, render_settings);
+
+        Logger::log(LogLevel::High, "vulkan_wrapper", "Headless Vulkan wrapper created successfully.");
+        return vulkan_wrapper;
+    }
+
+    //Everything past the presentation target is shared between windowed and headless containers so both render the exact same way.
+    fn from_parts(instance: Arc<Instance>, surface: Option<Arc<Surface>>, physical_device: Arc<PhysicalDevice>, logical_device: Arc<Device>, window: Option<Arc<Window>>, queue: Arc<Queue>, swapchain: Option<Arc<Swapchain>>, images: Vec<Arc<Image>>, memory_allocator: Arc<StandardMemoryAllocator>, viewport_info: &ViewportInfo, render_settings: RenderSettings) -> Self {
+        let image_views = VulkanContainer::create_image_views(&images);
+        let properties = physical_device.properties();
+        let sample_count = render_settings.sample_count(properties.framebuffer_color_sample_counts & properties.framebuffer_depth_sample_counts);
+        let shadow_settings = VulkanContainer::fit_shadow_settings(&physical_device, ShadowSettings::default());
+        let render_graph = VulkanContainer::create_render_graph(logical_device.clone(), memory_allocator.clone(), &image_views, sample_count, &shadow_settings);
         let command_buffer_allocator = VulkanContainer::create_command_buffer_allocator(logical_device.clone());
         let descriptor_set_allocator = VulkanContainer::create_descriptor_set_allocator(logical_device.clone());
-        let graphics_pipeline = VulkanContainer::create_graphics_pipeline(logical_device.clone(), render_pass.clone());
-        let framebuffers = VulkanContainer::create_frame_buffers(render_pass.clone(), image_views.clone(), memory_allocator.clone());
+        let shader_compiler = ShaderCompiler::new().expect("Failed to create shader compiler");
+        let pipeline_layout = VulkanContainer::create_pipeline_layout(logical_device.clone());
+        let (pipeline_cache, pipeline_cache_file) = VulkanContainer::create_pipeline_cache(logical_device.clone(), &physical_device, surface.is_none());
+        let pipelines = VulkanContainer::create_pipelines(&physical_device, logical_device.clone(), &render_graph, pipeline_layout.clone(), pipeline_cache.clone(), &shader_compiler);
+        //Headless renders are over before anyone could edit a shader, so they don't watch for changes.
+        let shader_watcher = if surface.is_none() {
+            None
+        } else {
+            match ShaderWatcher::new(Path::new(SHADER_DIRECTORY)) {
+                Ok(shader_watcher) => Some(shader_watcher),
+                Err(e) => {
+                    Logger::log(LogLevel::High, "vulkan_wrapper", &format!("Failed to watch {} for changes, shader hot reloading is disabled: {}", SHADER_DIRECTORY, e));
+                    None
+                },
+            }
+        };
+        let instance_buffer_allocator = VulkanContainer::create_instance_buffer_allocator(memory_allocator.clone());
+        let uniform_buffer_allocator = VulkanContainer::create_uniform_buffer_allocator(memory_allocator.clone());
+        let frames_in_flight = (0..render_settings.frames_in_flight.max(1)).map(|_| FrameInFlight::new()).collect();
+        let environment_map = VulkanContainer::upload_cubemap(logical_device.clone(), queue.clone(), memory_allocator.clone(), command_buffer_allocator.clone(), &CubemapPixels::solid(Vec4::new(0.0, 0.0, 0.0, 1.0))).expect("Failed to create the empty environment map");
+        let environment_sampler = Sampler::new(logical_device.clone(), SamplerCreateInfo::simple_repeat_linear_no_mipmap()).unwrap();
+        let post_sampler = Sampler::new(
+            logical_device.clone(),
+            SamplerCreateInfo {
+                mag_filter: Filter::Linear,
+                min_filter: Filter::Linear,
+                address_mode: [SamplerAddressMode::ClampToEdge; 3],
+                ..Default::default()
+            },
+        ).unwrap();
+        //Compares against the shadow map while filtering, so every tap already averages four texels.
+        let shadow_sampler = Sampler::new(
+            logical_device.clone(),
+            SamplerCreateInfo {
+                mag_filter: Filter::Linear,
+                min_filter: Filter::Linear,
+                address_mode: [SamplerAddressMode::ClampToEdge; 3],
+                compare: Some(CompareOp::LessOrEqual),
+                ..Default::default()
+            },
+        ).unwrap();
 
         let viewports = smallvec![Viewport {
             offset: [viewport_info.offset[0], viewport_info.offset[1]],
@@ -64,7 +207,7 @@ impl VulkanContainer {
             extent: [viewport_info.extent[0] as u32, viewport_info.extent[1] as u32],
         }];
 
-        let vulkan_wrapper = VulkanContainer {
+        let mut vulkan_container = VulkanContainer {
             instance,
             surface,
             physical_device,
@@ -74,26 +217,60 @@ impl VulkanContainer {
             swapchain,
             images,
             image_views,
-            render_pass,
+            render_graph,
+            sample_count,
             memory_allocator,
             command_buffer_allocator,
             descriptor_set_allocator,
-            graphics_pipeline,
-            framebuffers,
+            pipeline_layout,
+            pipeline_cache,
+            pipeline_cache_file,
+            pipelines,
+            shader_compiler,
+            shader_watcher,
             viewports,
             scissors,
             vulkan_objects: HashMap::new(),
+            texture_cache: ResourceCache::new(),
+            materials: HashMap::new(),
+            mesh_cache: ResourceCache::new(),
+            instance_buffer_allocator,
+            uniform_buffer_allocator,
+            ambient_light: Vec3::splat(0.15),
+            directional_light: DirectionalLight::default(),
+            point_lights: BTreeMap::new(),
+            environment_map,
+            environment_sampler,
+            skybox_enabled: false,
+            post_process_chain: PostProcessChain::default(),
+            post_process_luts: HashMap::new(),
+            post_sampler,
+            shadow_settings,
+            shadow_sampler,
+            font: None,
+            text_sections: vec![],
+            ui_textures: UiTextureStore::new(),
+            ui_frame: UiFrame::default(),
+            instancing: true,
+            frame_stats: FrameStats::default(),
+            frames_in_flight,
+            current_frame: 0,
+            previous_frame: 0,
+            swapchain_outdated: false,
+            offscreen_frame_error: None,
         };
 
-        Logger::log(LogLevel::High, "vulkan_wrapper", "Vulkan wrapper created successfully.");
-        return vulkan_wrapper;
+        vulkan_container.create_material(MaterialHandle::default(), &Material::textured(DEFAULT_TEXTURE_PATH));
+        vulkan_container.set_font(DEFAULT_FONT_PATH, DEFAULT_FONT_SIZE);
+        vulkan_container.save_pipeline_cache();
+
+        return vulkan_container;
     }
 
-    fn create_instance(event_loop: &ActiveEventLoop) -> Arc<Instance> {
+    fn create_instance(required_extensions: InstanceExtensions) -> Arc<Instance> {
         Logger::log(LogLevel::High, "vulkan_wrapper", "Creating Vulkan instance...");
         
         let library = vulkano::library::VulkanLibrary::new().unwrap();
-        let required_extensions = vulkano::swapchain::Surface::required_extensions(&event_loop).unwrap();
 
         let instance = vulkano::instance::Instance::new(
             library,
@@ -117,7 +294,7 @@ impl VulkanContainer {
         return surface;
     }
 
-    fn create_physical_device(instance: &Arc<Instance>, surface: &Arc<Surface>, device_extensions: &DeviceExtensions) -> (Arc<PhysicalDevice>, u32) {
+    fn create_physical_device(instance: &Arc<Instance>, surface: Option<&Arc<Surface>>, device_extensions: &DeviceExtensions) -> (Arc<PhysicalDevice>, u32) {
         Logger::log(LogLevel::High, "vulkan_wrapper", "Creating physical device...");
 
         let physical_devices = instance
@@ -135,7 +312,7 @@ impl VulkanContainer {
                     .enumerate()
                     .position(|(i, q)| {
                         q.queue_flags.contains(vulkano::device::QueueFlags::GRAPHICS)
-                            && p.surface_support(i as u32, &surface).unwrap_or(false)
+                            && surface.is_none_or(|surface| p.surface_support(i as u32, surface).unwrap_or(false))
                     })
                     .map(|q| (p, q as u32))
             })
@@ -163,6 +340,11 @@ impl VulkanContainer {
                     ..Default::default()
                 }],
                 enabled_extensions: *device_extensions,
+                //Only needed by the wireframe pipeline, which is left out on devices without it.
+                enabled_features: DeviceFeatures {
+                    fill_mode_non_solid: physical_device.supported_features().fill_mode_non_solid,
+                    ..DeviceFeatures::empty()
+                },
                 ..Default::default()
             },
         )
@@ -249,65 +431,143 @@ impl VulkanContainer {
         return image_views;
     }
 
-    fn create_render_pass(logical_device: Arc<Device>, swapchain: Arc<Swapchain>) -> Arc<RenderPass> {
-        Logger::log(LogLevel::High, "vulkan_wrapper", "Creating renderpass...");
+    fn create_offscreen_image(memory_allocator: Arc<StandardMemoryAllocator>, viewport_info: &ViewportInfo) -> Arc<Image> {
+        Logger::log(LogLevel::High, "vulkan_wrapper", "Creating offscreen image...");
 
-        let render_pass = vulkano::single_pass_renderpass!(
-            logical_device.clone(),
-            attachments: {
-                color: {
-                    format: swapchain.image_format(),
-                    samples: 1,
-                    load_op: Clear,
-                    store_op: Store,
-                },
-                depth: {
-                    format: Format::D16_UNORM,
-                    samples: 1,
-                    load_op: Clear,
-                    store_op: DontCare,
-                }
+        let image = Image::new(
+            memory_allocator,
+            ImageCreateInfo {
+                image_type: ImageType::Dim2d,
+                format: OFFSCREEN_FORMAT,
+                extent: [viewport_info.extent[0] as u32, viewport_info.extent[1] as u32, 1],
+                usage: ImageUsage::COLOR_ATTACHMENT | ImageUsage::TRANSFER_SRC,
+                ..Default::default()
             },
-            pass: {
-                color: [color],
-                depth_stencil: {depth},
-            }
-        ).unwrap();
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE,
+                ..Default::default()
+            },
+        ).expect("Could not create offscreen image");
 
-        Logger::log(LogLevel::High, "vulkan_wrapper", "Created renderpass.");
-        return render_pass;
+        Logger::log(LogLevel::High, "vulkan_wrapper", "Offscreen image created successfully.");
+        return image;
     }
 
-    fn create_graphics_pipeline(logical_device: Arc<Device>, render_pass: Arc<RenderPass>) -> Arc<GraphicsPipeline> {
-        Logger::log(LogLevel::High, "vulkan_wrapper", "Creating graphics pipeline...");
+    //Every pass the container records and what they pass between each other. The shadow map is drawn first and sampled by the scene.
+    //With MSAA the scene is drawn into multisampled attachments and resolved into the first HDR target.
+    //Bloom gets as many levels as the presented size allows, the overlay pass writes the end of the chain to the presented image and draws text and UI on top.
+    fn describe_render_graph(present_format: Format, extent: [u32; 2], sample_count: SampleCount, shadow_settings: &ShadowSettings) -> RenderGraphDescription {
+        let black = AttachmentLoad::Clear(ClearValue::Float([0.0, 0.0, 0.0, 1.0]));
+        let mut description = RenderGraphDescription::new(present_format);
 
-        let vs_path = Path::new(env!("OUT_DIR")).join("shader.vert.spv");
-        let fs_path = Path::new(env!("OUT_DIR")).join("shader.frag.spv");
+        let shadow_map_size = [shadow_settings.resolution * shadow_settings.cascades as u32, shadow_settings.resolution];
+        description.add_attachment("shadow_map", AttachmentDeclaration::new(SHADOW_FORMAT, AttachmentSize::Fixed(shadow_map_size)));
+        description.add_pass(PassDeclaration::new("shadow").depth("shadow_map", AttachmentLoad::Clear(ClearValue::Depth(1.0))));
 
-        let vs = VulkanContainer::load_shader(logical_device.clone(), vs_path);
-        let fs = VulkanContainer::load_shader(logical_device.clone(), fs_path);
+        for target in HDR_TARGETS {
+            description.add_attachment(target, AttachmentDeclaration::new(HDR_FORMAT, AttachmentSize::Full));
+        }
+        description.add_attachment("depth", AttachmentDeclaration::multisampled(Format::D16_UNORM, sample_count));
+        let scene = PassDeclaration::new("scene").depth("depth", AttachmentLoad::Clear(ClearValue::Depth(1.0))).reads("shadow_map");
+        if sample_count != SampleCount::Sample1 {
+            description.add_attachment("scene_multisampled", AttachmentDeclaration::multisampled(HDR_FORMAT, sample_count));
+            description.add_pass(scene.color("scene_multisampled", black).resolve(HDR_TARGETS[0]));
+        } else {
+            description.add_pass(scene.color(HDR_TARGETS[0], black));
+        }
 
-        let stages = smallvec![
-            PipelineShaderStageCreateInfo::new(vs.entry_point("main").unwrap()),
-            PipelineShaderStageCreateInfo::new(fs.entry_point("main").unwrap()),
-        ];
+        //Every post processing pass draws over its whole target, so nothing there is ever loaded.
+        let levels = bloom_level_extents(extent).len();
+        for level in 0..levels {
+            let downsampled = VulkanContainer::bloom_downsampled(level);
+            description.add_attachment(&downsampled, AttachmentDeclaration::new(HDR_FORMAT, AttachmentSize::Divided(2 << level)));
+            let pass = PassDeclaration::new(&downsampled).color(&downsampled, AttachmentLoad::DontCare);
+            description.add_pass(match level {
+                0 => pass.reads(HDR_TARGETS[0]).reads(HDR_TARGETS[1]),
+                _ => pass.reads(&VulkanContainer::bloom_downsampled(level - 1)),
+            });
+        }
+        for level in (0..levels - 1).rev() {
+            let upsampled = VulkanContainer::bloom_upsampled(level);
+            let smaller = if level == levels - 2 { VulkanContainer::bloom_downsampled(level + 1) } else { VulkanContainer::bloom_upsampled(level + 1) };
+            description.add_attachment(&upsampled, AttachmentDeclaration::new(HDR_FORMAT, AttachmentSize::Divided(2 << level)));
+            description.add_pass(PassDeclaration::new(&upsampled).color(&upsampled, AttachmentLoad::DontCare).reads(&smaller).reads(&VulkanContainer::bloom_downsampled(level)));
+        }
+        let bloom = if levels > 1 { VulkanContainer::bloom_upsampled(0) } else { VulkanContainer::bloom_downsampled(0) };
+
+        for target in 0..2 {
+            description.add_pass(PassDeclaration::new(POST_PASSES[target]).color(HDR_TARGETS[target], AttachmentLoad::DontCare).reads(HDR_TARGETS[1 - target]).reads(&bloom));
+        }
+
+        //The presented image is cleared first since the viewport doesn't have to cover all of it.
+        description.add_pass(PassDeclaration::new("overlay").color(PRESENT_ATTACHMENT, black).reads(HDR_TARGETS[0]).reads(HDR_TARGETS[1]));
+
+        return description;
+    }
+
+    //Bloom levels use the same name for the attachment and the pass drawing it.
+    fn bloom_downsampled(level: usize) -> String {
+        return format!("bloom_down_{}", level);
+    }
+
+    fn bloom_upsampled(level: usize) -> String {
+        return format!("bloom_up_{}", level);
+    }
 
+    fn create_render_graph(logical_device: Arc<Device>, memory_allocator: Arc<StandardMemoryAllocator>, image_views: &[Arc<ImageView>], sample_count: SampleCount, shadow_settings: &ShadowSettings) -> RenderGraph {
+        let [width, height, _] = image_views[0].image().extent();
+        let description = VulkanContainer::describe_render_graph(image_views[0].format(), [width, height], sample_count, shadow_settings);
+
+        return RenderGraph::new(logical_device, memory_allocator, &description, image_views).expect("Failed to build the render graph");
+    }
+
+    fn create_pipeline_layout(logical_device: Arc<Device>) -> Arc<PipelineLayout> {
+        //Set 0 holds what changes once per frame, set 1 what changes per material, so the frame data is bound once and only the material gets swapped between batches.
+        //Set 2 is only used by post processing, the image the pass reads, an optional second image like a lookup table and the parameters of the effect.
         let mut uniform_binding = DescriptorSetLayoutBinding::descriptor_type(DescriptorType::UniformBuffer);
-        uniform_binding.stages = ShaderStages::VERTEX;
+        uniform_binding.stages = ShaderStages::FRAGMENT;
+
+        let mut environment_binding = DescriptorSetLayoutBinding::descriptor_type(DescriptorType::CombinedImageSampler);
+        environment_binding.stages = ShaderStages::FRAGMENT;
+
+        let mut shadow_map_binding = DescriptorSetLayoutBinding::descriptor_type(DescriptorType::CombinedImageSampler);
+        shadow_map_binding.stages = ShaderStages::FRAGMENT;
 
         let mut texture_binding = DescriptorSetLayoutBinding::descriptor_type(DescriptorType::CombinedImageSampler);
         texture_binding.stages = ShaderStages::FRAGMENT;
 
-        let bindings = BTreeMap::from([
-            ( 0, uniform_binding ),
-            ( 1, texture_binding ),
-        ]);
+        let mut material_binding = DescriptorSetLayoutBinding::descriptor_type(DescriptorType::UniformBuffer);
+        material_binding.stages = ShaderStages::FRAGMENT;
+
+        let mut post_source_binding = DescriptorSetLayoutBinding::descriptor_type(DescriptorType::CombinedImageSampler);
+        post_source_binding.stages = ShaderStages::FRAGMENT;
+
+        let mut post_lut_binding = DescriptorSetLayoutBinding::descriptor_type(DescriptorType::CombinedImageSampler);
+        post_lut_binding.stages = ShaderStages::FRAGMENT;
 
+        let mut post_uniform_binding = DescriptorSetLayoutBinding::descriptor_type(DescriptorType::UniformBuffer);
+        post_uniform_binding.stages = ShaderStages::FRAGMENT;
 
-        let descriptor_set_layout = DescriptorSetLayout::new(
+        let frame_set_layout = DescriptorSetLayout::new(
             logical_device.clone(),
             DescriptorSetLayoutCreateInfo {
-                bindings,
+                bindings: BTreeMap::from([( 0, uniform_binding ), ( 1, environment_binding ), ( 2, shadow_map_binding )]),
+                ..Default::default()
+            },
+        );
+
+        let object_set_layout = DescriptorSetLayout::new(
+            logical_device.clone(),
+            DescriptorSetLayoutCreateInfo {
+                bindings: BTreeMap::from([( 0, texture_binding ), ( 1, material_binding )]),
+                ..Default::default()
+            },
+        );
+
+        let post_process_set_layout = DescriptorSetLayout::new(
+            logical_device.clone(),
+            DescriptorSetLayoutCreateInfo {
+                bindings: BTreeMap::from([( 0, post_source_binding ), ( 1, post_lut_binding ), ( 2, post_uniform_binding )]),
                 ..Default::default()
             },
         );
@@ -315,7 +575,7 @@ impl VulkanContainer {
         let pipeline_layout = PipelineLayout::new(
             logical_device.clone(),
             PipelineLayoutCreateInfo {
-                set_layouts: vec![descriptor_set_layout.unwrap()],
+                set_layouts: vec![frame_set_layout.unwrap(), object_set_layout.unwrap(), post_process_set_layout.unwrap()],
                 push_constant_ranges: vec![PushConstantRange {
                     stages: ShaderStages::VERTEX,
                     offset: 0,
@@ -325,16 +585,120 @@ impl VulkanContainer {
             },
         );
 
-        let mut pipeline_info = GraphicsPipelineCreateInfo::layout(pipeline_layout.unwrap().clone());
+        return pipeline_layout.unwrap();
+    }
+
+    //Pipelines compiled in an earlier run are loaded back from disk, so startup doesn't have to compile them from scratch every time.
+    //Headless containers, like the ones tests render with, keep theirs in memory and never touch the user's cache directory.
+    fn create_pipeline_cache(logical_device: Arc<Device>, physical_device: &PhysicalDevice, headless: bool) -> (Arc<PipelineCache>, Option<PipelineCacheFile>) {
+        Logger::log(LogLevel::High, "vulkan_wrapper", "Creating pipeline cache...");
+
+        let pipeline_cache_file = if headless { None } else { PipelineCacheFile::new(physical_device) };
+        let initial_data = match pipeline_cache_file.as_ref().and_then(|file| file.load()) {
+            Some(data) => {
+                Logger::log(LogLevel::High, "vulkan_wrapper", &format!("Loaded {} bytes of pipeline cache from {}.", data.len(), pipeline_cache_file.as_ref().unwrap().get_path().display()));
+                data
+            },
+            None => vec![],
+        };
+
+        //The data is only used when our header matched this device and driver, the driver validates it again on top of that.
+        let pipeline_cache = unsafe {
+            PipelineCache::new(logical_device.clone(), PipelineCacheCreateInfo { initial_data, ..Default::default() })
+        }.or_else(|_| unsafe {
+            PipelineCache::new(logical_device, PipelineCacheCreateInfo::default())
+        }).expect("Failed to create pipeline cache");
+
+        Logger::log(LogLevel::High, "vulkan_wrapper", "Pipeline cache created successfully.");
+        return (pipeline_cache, pipeline_cache_file);
+    }
+
+    fn save_pipeline_cache(&self) {
+        let Some(pipeline_cache_file) = &self.pipeline_cache_file else {
+            return;
+        };
+
+        let result = match self.pipeline_cache.get_data() {
+            Ok(data) => pipeline_cache_file.save(&data).map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+
+        if let Err(e) = result {
+            Logger::log(LogLevel::High, "vulkan_wrapper", &format!("Failed to save pipeline cache to {}: {}", pipeline_cache_file.get_path().display(), e));
+        }
+    }
+
+    //The opaque pipeline is what everything falls back to so it has to exist, any other pipeline that fails to build is logged and left out.
+    fn create_pipelines(physical_device: &PhysicalDevice, logical_device: Arc<Device>, render_graph: &RenderGraph, pipeline_layout: Arc<PipelineLayout>, pipeline_cache: Arc<PipelineCache>, shader_compiler: &ShaderCompiler) -> PipelineRegistry {
+        let mut pipelines = PipelineRegistry::new();
+
+        for description in [PipelineDescription::opaque(), PipelineDescription::shadow(), PipelineDescription::alpha_blended(), PipelineDescription::wireframe(), PipelineDescription::unlit(), PipelineDescription::skybox(), PipelineDescription::debug_lines(), PipelineDescription::debug_lines_overlay(), PipelineDescription::text(), PipelineDescription::ui(), PipelineDescription::post_process("post_tonemap"), PipelineDescription::post_process("post_gamma"), PipelineDescription::post_process("post_vignette"), PipelineDescription::post_process("post_fxaa"), PipelineDescription::post_process("post_color_grading"), PipelineDescription::post_process("post_bloom_prefilter"), PipelineDescription::post_process("post_bloom_downsample"), PipelineDescription::post_process("post_bloom_upsample"), PipelineDescription::post_process("post_bloom_composite"), PipelineDescription::post_output()] {
+            if description.polygon_mode != PolygonMode::Fill && !physical_device.supported_features().fill_mode_non_solid {
+                Logger::log(LogLevel::High, "vulkan_wrapper", &format!("Device doesn't support fill_mode_non_solid, skipping the {} pipeline.", description.name));
+                continue;
+            }
+
+            let pass = render_graph.get_render_pass(VulkanContainer::graph_pass(description.pass));
+            match VulkanContainer::create_graphics_pipeline(logical_device.clone(), pass, pipeline_layout.clone(), pipeline_cache.clone(), shader_compiler, &description) {
+                Ok(pipeline) => pipelines.insert(description, pipeline),
+                Err(e) if description.name == DEFAULT_PIPELINE => panic!("Failed to create the {} pipeline: {}", description.name, e),
+                Err(e) => Logger::log(LogLevel::High, "vulkan_wrapper", &format!("Failed to create the {} pipeline: {}", description.name, e)),
+            }
+        }
+
+        return pipelines;
+    }
+
+    //Pipelines only need a compatible render pass, so every post processing and bloom pass shares the pipelines made for the first one.
+    fn graph_pass(pass: PipelinePass) -> &'static str {
+        return match pass {
+            PipelinePass::Shadow => "shadow",
+            PipelinePass::Scene => "scene",
+            PipelinePass::PostProcess => POST_PASSES[0],
+            PipelinePass::Overlay => "overlay",
+        };
+    }
+
+    fn create_graphics_pipeline(logical_device: Arc<Device>, render_pass: Arc<RenderPass>, pipeline_layout: Arc<PipelineLayout>, pipeline_cache: Arc<PipelineCache>, shader_compiler: &ShaderCompiler, description: &PipelineDescription) -> Result<Arc<GraphicsPipeline>, Box<dyn std::error::Error>> {
+        Logger::log(LogLevel::High, "vulkan_wrapper", &format!("Creating {} graphics pipeline...", description.name));
+
+        let vs = VulkanContainer::load_shader(logical_device.clone(), shader_compiler, Path::new(&description.vertex_shader))?;
+        let fs = VulkanContainer::load_shader(logical_device.clone(), shader_compiler, Path::new(&description.fragment_shader))?;
+
+        let stages = smallvec![
+            PipelineShaderStageCreateInfo::new(vs.entry_point("main").ok_or("Vertex shader has no main entry point")?),
+            PipelineShaderStageCreateInfo::new(fs.entry_point("main").ok_or("Fragment shader has no main entry point")?),
+        ];
+
+        let mut pipeline_info = GraphicsPipelineCreateInfo::layout(pipeline_layout);
 
         pipeline_info.stages = stages;
-        pipeline_info.vertex_input_state = Some(
-            VertexDefinition::definition(
-                &<vertex::Vertex as vulkano::pipeline::graphics::vertex_input::Vertex>::per_vertex(),
+        pipeline_info.vertex_input_state = Some(match description.vertex_input {
+            VertexInput::Mesh => VertexDefinition::definition(
+                &[
+                    <vertex::Vertex as vulkano::pipeline::graphics::vertex_input::Vertex>::per_vertex(),
+                    <InstanceData as vulkano::pipeline::graphics::vertex_input::Vertex>::per_instance(),
+                ],
                 &vs.entry_point("main").unwrap()
-            ).unwrap()
-        );
-        pipeline_info.input_assembly_state = Some(InputAssemblyState::default());
+            )?,
+            VertexInput::Generated => VertexInputState::default(),
+            VertexInput::DebugLines => VertexDefinition::definition(
+                &[<DebugVertex as vulkano::pipeline::graphics::vertex_input::Vertex>::per_vertex()],
+                &vs.entry_point("main").unwrap()
+            )?,
+            VertexInput::Text => VertexDefinition::definition(
+                &[<TextVertex as vulkano::pipeline::graphics::vertex_input::Vertex>::per_vertex()],
+                &vs.entry_point("main").unwrap()
+            )?,
+            VertexInput::Ui => VertexDefinition::definition(
+                &[<UiVertex as vulkano::pipeline::graphics::vertex_input::Vertex>::per_vertex()],
+                &vs.entry_point("main").unwrap()
+            )?,
+        });
+        pipeline_info.input_assembly_state = Some(InputAssemblyState {
+            topology: description.topology,
+            ..Default::default()
+        });
         pipeline_info.dynamic_state = HashSet::from_iter([
             DynamicState::ViewportWithCount,
             DynamicState::ScissorWithCount,
@@ -344,67 +708,43 @@ impl VulkanContainer {
             scissors: smallvec![],
             ..Default::default()
         });
-        pipeline_info.rasterization_state = Some(RasterizationState::default());
-        pipeline_info.multisample_state = Some(MultisampleState::default());
+        pipeline_info.rasterization_state = Some(RasterizationState {
+            polygon_mode: description.polygon_mode,
+            cull_mode: description.cull_mode,
+            ..Default::default()
+        });
+        let subpass = Subpass::from(render_pass.clone(), 0).unwrap();
+        pipeline_info.multisample_state = Some(MultisampleState {
+            rasterization_samples: subpass.num_samples().unwrap_or(SampleCount::Sample1),
+            ..Default::default()
+        });
+        //Depth only passes have no color attachments to blend into.
         pipeline_info.color_blend_state = Some(ColorBlendState {
-            attachments: vec![
-                ColorBlendAttachmentState {
-                    blend: None,           // no blending
-                    color_write_mask: ColorComponents::all(),
-                    ..Default::default()
-                }
-            ],
+            attachments: (0..subpass.num_color_attachments()).map(|_| ColorBlendAttachmentState {
+                blend: description.blend.clone(),
+                color_write_mask: ColorComponents::all(),
+                ..Default::default()
+            }).collect(),
             ..Default::default()
         });
-        pipeline_info.subpass = Some(Subpass::from(render_pass.clone(), 0).unwrap().into());
-
-        let depth_sencil_state = DepthStencilState { depth: Some(DepthState::simple()), ..Default::default()};
-        pipeline_info.depth_stencil_state = Some(depth_sencil_state);
+        //Passes without a depth attachment must not have any depth state at all.
+        if subpass.subpass_desc().depth_stencil_attachment.is_some() {
+            let depth = if description.depth_test { Some(DepthState { write_enable: description.depth_write, compare_op: CompareOp::Less }) } else { None };
+            let depth_sencil_state = DepthStencilState { depth, ..Default::default()};
+            pipeline_info.depth_stencil_state = Some(depth_sencil_state);
+        }
+        pipeline_info.subpass = Some(subpass.into());
         
         let pipeline = GraphicsPipeline::new(
             logical_device.clone(),
-            None,
+            Some(pipeline_cache),
             pipeline_info
-        ).unwrap();
+        )?;
 
-        Logger::log(LogLevel::High, "vulkan_wrapper", "Graphics pipeline created successfully.");
-        return pipeline;
+        Logger::log(LogLevel::High, "vulkan_wrapper", &format!("{} graphics pipeline created successfully.", description.name));
+        return Ok(pipeline);
     }
 
-    fn create_frame_buffers(render_pass: Arc<RenderPass>, image_views: Vec<Arc<ImageView>>, memory_allocator: Arc<StandardMemoryAllocator>) -> Vec<Arc<Framebuffer>> {
-        Logger::log(LogLevel::High, "vulkan_wrapper", "Creating frame buffer...");
-
-        let mut framebuffers: Vec<Arc<vulkano::render_pass::Framebuffer>> = vec!();
-        for image_view in image_views.iter() {
-            let depth_image_create_info = ImageCreateInfo { 
-                image_type: ImageType::Dim2d,
-                format: render_pass.attachments()[1].format, 
-                extent: [image_view.image().extent()[0], image_view.image().extent()[1], 1],
-                usage: ImageUsage::DEPTH_STENCIL_ATTACHMENT,
-                ..Default::default()
-            };
-            let depth_image_allocation_info = AllocationCreateInfo {
-                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE,
-                ..Default::default()
-            };
-            let depth_image = Image::new(memory_allocator.clone(), depth_image_create_info, depth_image_allocation_info).unwrap();
-            let depth_view = ImageView::new_default(depth_image).unwrap();
-
-            framebuffers.push(
-                vulkano::render_pass::Framebuffer::new(
-                    render_pass.clone(),
-                    vulkano::render_pass::FramebufferCreateInfo {
-                        attachments: vec![image_view.clone(), depth_view.clone()],
-                        ..Default::default()
-                    }
-                ).unwrap()
-            );
-        }
-
-        Logger::log(LogLevel::High, "vulkan_wrapper", "Created frame buffer...");
-        return framebuffers;
-    }
-    
     fn create_memory_allocator(logical_device: Arc<Device>) -> Arc<StandardMemoryAllocator> {
         Logger::log(LogLevel::High, "vulkan_wrapper", "Creating memory allocator...");
 
@@ -414,6 +754,29 @@ impl VulkanContainer {
         return memory_allocator;
     }
 
+    fn create_uniform_buffer_allocator(memory_allocator: Arc<StandardMemoryAllocator>) -> SubbufferAllocator {
+        return SubbufferAllocator::new(
+            memory_allocator,
+            SubbufferAllocatorCreateInfo {
+                buffer_usage: BufferUsage::UNIFORM_BUFFER,
+                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            },
+        );
+    }
+
+    //Instance data, debug lines, text and UI meshes are rewritten every frame, the allocator hands out fresh subbuffers and recycles the ones the GPU is done with.
+    fn create_instance_buffer_allocator(memory_allocator: Arc<StandardMemoryAllocator>) -> SubbufferAllocator {
+        return SubbufferAllocator::new(
+            memory_allocator,
+            SubbufferAllocatorCreateInfo {
+                buffer_usage: BufferUsage::VERTEX_BUFFER | BufferUsage::INDEX_BUFFER,
+                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            },
+        );
+    }
+
     fn create_command_buffer_allocator(logical_device: Arc<Device>) -> Arc<StandardCommandBufferAllocator> {
         return Arc::new(StandardCommandBufferAllocator::new(logical_device.clone(), Default::default()));
     }
@@ -427,136 +790,1022 @@ impl VulkanContainer {
         return descriptor_set_allocator;
     }
 
-    fn load_shader(device: Arc<Device>, path: impl AsRef<std::path::Path>) -> Arc<ShaderModule> {
-        let mut file = File::open(path).expect("Failed to open shader file");
-        let mut bytes = vec![];
-        
-        file.read_to_end(&mut bytes).expect("Failed to read shader file");
-    
-        let words = shader::spirv::bytes_to_words(&bytes);
-        
-        unsafe {
-            ShaderModule::new(
-                device,
-                ShaderModuleCreateInfo::new(&words.unwrap())).expect("Failed to create shader module")
+    fn load_shader(device: Arc<Device>, shader_compiler: &ShaderCompiler, path: &Path) -> Result<Arc<ShaderModule>, Box<dyn std::error::Error>> {
+        let words = shader_compiler.compile(path)?;
+
+        let shader_module = unsafe {
+            ShaderModule::new(device, ShaderModuleCreateInfo::new(&words))?
+        };
+
+        return Ok(shader_module);
+    }
+
+    //Rebuilds every pipeline using a shader that was saved. A shader that fails to compile is logged and the last working pipeline keeps running.
+    fn reload_changed_shaders(&mut self) {
+        let Some(shader_watcher) = &self.shader_watcher else {
+            return;
+        };
+
+        let mut descriptions: Vec<PipelineDescription> = vec![];
+        for changed_shader in shader_watcher.changed_shaders() {
+            for description in self.pipelines.descriptions_using_shader(&changed_shader) {
+                if !descriptions.iter().any(|added| added.name == description.name) {
+                    descriptions.push(description);
+                }
+            }
+        }
+
+        if descriptions.is_empty() {
+            return;
+        }
+
+        for description in descriptions {
+            Logger::log(LogLevel::Medium, "vulkan_wrapper", &format!("Shader source changed, reloading {} pipeline...", description.name));
+
+            let pass = self.render_graph.get_render_pass(VulkanContainer::graph_pass(description.pass));
+            match VulkanContainer::create_graphics_pipeline(self.logical_device.clone(), pass, self.pipeline_layout.clone(), self.pipeline_cache.clone(), &self.shader_compiler, &description) {
+                Ok(pipeline) => {
+                    Logger::log(LogLevel::Medium, "vulkan_wrapper", &format!("{} pipeline reloaded successfully.", description.name));
+                    self.pipelines.insert(description, pipeline);
+                },
+                Err(e) => Logger::log(LogLevel::High, "vulkan_wrapper", &format!("Failed to reload {} pipeline, keeping the previous one: {}", description.name, e)),
+            }
         }
+
+        self.save_pipeline_cache();
     }
     
-    pub fn create_vulkan_object(&mut self, id: usize, vertices: Vec<Vertex>, object_transform: Transform, texture_path: &str) {
+    pub fn create_vulkan_object(&mut self, id: usize, mesh: &Mesh, object_transform: Transform, material: MaterialHandle) {
         Logger::log(LogLevel::High, "vulkan_wrapper", "Creating vulkan object...");
-        
-        let vertex_buffer = Buffer::from_iter(
+
+        let cache_key = mesh.get_cache_key();
+        let gpu_mesh = match self.mesh_cache.acquire(&cache_key) {
+            Some(gpu_mesh) => gpu_mesh,
+            None => {
+                let gpu_mesh = self.upload_mesh(mesh, cache_key.clone());
+                self.mesh_cache.insert(&cache_key, gpu_mesh)
+            },
+        };
+
+        if !self.materials.contains_key(&material) {
+            Logger::log(LogLevel::High, "vulkan_wrapper", &format!("Material {} does not exist (yet), the object is drawn with the default material until it does.", material.get_name()));
+        }
+
+        let vulkan_object = VulkanObject::new(gpu_mesh, object_transform, material);
+        if let Some(replaced_object) = self.vulkan_objects.insert(id, vulkan_object) {
+            self.release_object_resources(&replaced_object);
+        }
+        Logger::log(LogLevel::High, "vulkan_wrapper", "Vulkan object created successfully.");
+    }
+
+    //Registers a material under a handle, replacing a material with the same handle changes the look of every object using it.
+    pub fn create_material(&mut self, handle: MaterialHandle, material: &Material) {
+        Logger::log(LogLevel::High, "vulkan_wrapper", &format!("Creating material {}...", handle.get_name()));
+
+        if !self.pipelines.is_mesh_pipeline(&material.pipeline) {
+            Logger::log(LogLevel::High, "vulkan_wrapper", &format!("Material {} asks for pipeline {} which doesn't exist or can't draw meshes, falling back to {}.", handle.get_name(), material.pipeline, DEFAULT_PIPELINE));
+        }
+
+        let texture = match self.acquire_texture(material.base_color_texture.as_deref()) {
+            Ok(texture) => texture,
+            Err(e) => {
+                Logger::log(LogLevel::High, "vulkan_wrapper", &format!("Failed to load the texture of material {}: {}", handle.get_name(), e));
+                return;
+            },
+        };
+
+        let sampler = Sampler::new(
+            self.logical_device.clone(),
+            SamplerCreateInfo {
+                mag_filter: material.sampler.filter,
+                min_filter: material.sampler.filter,
+                address_mode: [material.sampler.address_mode; 3],
+                ..Default::default()
+            },
+        ).unwrap();
+
+        let material_buffer = Buffer::from_data(
             self.memory_allocator.clone(),
             BufferCreateInfo {
-                usage: BufferUsage::VERTEX_BUFFER,
+                usage: BufferUsage::UNIFORM_BUFFER,
                 ..Default::default()
             },
             AllocationCreateInfo {
                 memory_type_filter: MemoryTypeFilter::PREFER_DEVICE | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
                 ..Default::default()
             },
-            vertices.iter().cloned()
-        ).expect("Failed to create vertex buffer");
-
-        let (texture_view, texture_sample) = self.load_png_texture(texture_path).unwrap();
+            MaterialUniforms::new(material),
+        ).expect("Failed to create material buffer");
 
         let descriptor_set = DescriptorSet::new(
-                self.descriptor_set_allocator.clone(),
-                self.graphics_pipeline.layout().set_layouts().get(0).unwrap().clone(),
-                [
-                    WriteDescriptorSet::image_view_sampler(
-                        1, // binding index
-                        texture_view.clone(),
-                        texture_sample.clone(),
-                    ),
-                ],
-    [],
-            ).unwrap();
+            self.descriptor_set_allocator.clone(),
+            self.pipeline_layout.set_layouts().get(1).unwrap().clone(),
+            [
+                WriteDescriptorSet::image_view_sampler(0, texture.get_image_view(), sampler),
+                WriteDescriptorSet::buffer(1, material_buffer),
+            ],
+            [],
+        ).unwrap();
 
-        let vulkan_object = VulkanObject::new(vertex_buffer, object_transform, descriptor_set);
-        self.vulkan_objects.insert(id, vulkan_object);
-        Logger::log(LogLevel::High, "vulkan_wrapper", "Vulkan object created successfully.");
-    }
+        let gpu_material = Arc::new(GpuMaterial::new(handle.clone(), material.pipeline.clone(), texture, descriptor_set));
+        if let Some(replaced_material) = self.materials.insert(handle, gpu_material) {
+            self.release_texture(replaced_material.get_texture().get_path());
+        }
 
-    pub fn delete_vulkan_object(&mut self, index: usize) {
-        Logger::log(LogLevel::High, "vulkan_wrapper", "Deleting vulkan object...");
+        Logger::log(LogLevel::High, "vulkan_wrapper", "Material created successfully.");
+    }
 
-        if index >= self.vulkan_objects.len() {
-            Logger::log(LogLevel::High, "vulkan_wrapper", "Index out of bounds for vulkan object deletion.");
-            return;
+    fn acquire_texture(&mut self, texture_path: Option<&str>) -> Result<Arc<Texture>, Box<dyn std::error::Error>> {
+        let key = texture_path.unwrap_or(WHITE_TEXTURE_KEY);
+        if let Some(texture) = self.texture_cache.acquire(key) {
+            return Ok(texture);
         }
 
-        self.vulkan_objects.remove(&index);
+        let texture = match texture_path {
+            Some(path) => self.create_texture(path)?,
+            None => Texture::new(WHITE_TEXTURE_KEY, self.upload_texture(1, 1, vec![255, 255, 255, 255])?),
+        };
 
-        Logger::log(LogLevel::High, "vulkan_wrapper", "Vulkan object deleted successfully.");
+        return Ok(self.texture_cache.insert(key, texture));
     }
 
-    fn create_command_buffer(&mut self, image_index: usize, view_projection: Mat4) -> Arc<PrimaryAutoCommandBuffer> {
-        let mut builder = AutoCommandBufferBuilder::primary(
-            self.command_buffer_allocator.clone(),
-            self.logical_device.active_queue_family_indices()[0],
-            CommandBufferUsage::OneTimeSubmit
-        ).unwrap();
-
-        builder.begin_render_pass(
-                RenderPassBeginInfo {
-                    clear_values: vec![Some([0.0, 0.0, 0.0, 1.0].into()), Some(ClearValue::Depth(1.0))], // background color
-                    ..RenderPassBeginInfo::framebuffer(self.framebuffers[image_index].clone())
-                },
-                SubpassBeginInfo {
-                    contents: SubpassContents::Inline,
-                    ..Default::default()
-                },
-            ).unwrap();
-        builder.bind_pipeline_graphics(self.graphics_pipeline.clone()).unwrap();
-        builder.set_viewport_with_count(self.viewports.clone()).unwrap();
-        builder.set_scissor_with_count(self.scissors.clone()).unwrap();
-
-        for vulkan_object in self.vulkan_objects.iter() {
-            let model = Mat4::from_translation(vulkan_object.1.get_transform().position);
-            let mvp = view_projection * model;
-            let push_constants = PushConstants::new(mvp);
+    fn release_texture(&mut self, texture_path: &str) {
+        if self.texture_cache.release(texture_path) {
+            Logger::log(LogLevel::High, "vulkan_wrapper", &format!("Texture {} is no longer used and was freed, {} textures still loaded.", texture_path, self.texture_cache.len()));
+        }
+    }
 
-            let vertex_buffer = vulkan_object.1.get_buffer().clone();
-            builder.bind_vertex_buffers(0, vertex_buffer.clone()).unwrap();
+    fn create_texture(&mut self, texture_path: &str) -> Result<Texture, Box<dyn std::error::Error>> {
+        Logger::log(LogLevel::High, "vulkan_wrapper", &format!("Loading texture {}...", texture_path));
 
-            builder.bind_descriptor_sets(
-            PipelineBindPoint::Graphics,
-            self.graphics_pipeline.layout().clone(),
-            0,
-            vulkan_object.1.get_descriptor_set(),
-        ).unwrap();
-            builder.push_constants(self.graphics_pipeline.layout().clone(), 0, push_constants).unwrap();
-            unsafe { builder.draw(vertex_buffer.len().try_into().unwrap(), 1, 0, 0).unwrap() };
-        }
+        let texture_view = self.load_png_texture(texture_path)?;
 
-        builder.end_render_pass(SubpassEndInfo::default()).unwrap();
-        let command_buffer = builder.build().unwrap();
-        
-        return command_buffer;
+        Logger::log(LogLevel::High, "vulkan_wrapper", "Texture loaded successfully.");
+        return Ok(Texture::new(texture_path, texture_view));
     }
 
-    pub fn draw_frame(&mut self, camera_location: Vec3, camera_rotation: Vec3) {
-        let (image_index, _, acquire_future) = swapchain::acquire_next_image(self.swapchain.clone(), None).unwrap();
-        let view_projection = VulkanContainer::make_view_projection(self.viewports[0].extent[0] as f32 / self.viewports[0].extent[1] as f32, camera_location, camera_rotation);
-        
-        let command_buffer = self.create_command_buffer(image_index.try_into().unwrap(), view_projection);
-        let future = GpuFuture::then_signal_fence_and_flush(
-            GpuFuture::then_swapchain_present(
-                GpuFuture::then_execute(acquire_future, self.queue.clone(),
-                command_buffer).unwrap(),
+    fn upload_mesh(&mut self, mesh: &Mesh, cache_key: String) -> GpuMesh {
+        Logger::log(LogLevel::High, "vulkan_wrapper", &format!("Uploading mesh {}...", mesh.get_name()));
+
+        let vertex_buffer = Buffer::from_iter(
+            self.memory_allocator.clone(),
+            BufferCreateInfo {
+                usage: BufferUsage::VERTEX_BUFFER,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            },
+            mesh.get_vertices().iter().cloned()
+        ).expect("Failed to create vertex buffer");
+
+        let index_buffer = Buffer::from_iter(
+            self.memory_allocator.clone(),
+            BufferCreateInfo {
+                usage: BufferUsage::INDEX_BUFFER,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            },
+            mesh.get_indices().iter().cloned()
+        ).expect("Failed to create index buffer");
+
+        Logger::log(LogLevel::High, "vulkan_wrapper", "Mesh uploaded successfully.");
+        return GpuMesh::new(mesh.get_name(), cache_key, vertex_buffer, index_buffer, mesh.get_bounds());
+    }
+
+    fn release_object_resources(&mut self, vulkan_object: &VulkanObject) {
+        let mesh = vulkan_object.get_mesh();
+        if self.mesh_cache.release(mesh.get_cache_key()) {
+            Logger::log(LogLevel::High, "vulkan_wrapper", &format!("Mesh {} is no longer used and was freed, {} meshes still loaded.", mesh.get_name(), self.mesh_cache.len()));
+        }
+    }
+
+    pub fn update_vulkan_object_transform(&mut self, id: usize, object_transform: Transform) {
+        match self.vulkan_objects.get_mut(&id) {
+            Some(vulkan_object) => vulkan_object.set_transform(object_transform),
+            None => Logger::log(LogLevel::High, "vulkan_wrapper", &format!("No vulkan object with ID {} to update the transform of.", id)),
+        }
+    }
+
+    pub fn delete_vulkan_object(&mut self, index: usize) {
+        Logger::log(LogLevel::High, "vulkan_wrapper", "Deleting vulkan object...");
+
+        let Some(vulkan_object) = self.vulkan_objects.remove(&index) else {
+            Logger::log(LogLevel::High, "vulkan_wrapper", "No vulkan object with this ID to delete.");
+            return;
+        };
+
+        self.release_object_resources(&vulkan_object);
+
+        Logger::log(LogLevel::High, "vulkan_wrapper", "Vulkan object deleted successfully.");
+    }
+
+    fn create_command_buffer(&mut self, image_index: usize, view_projection: Mat4, camera_position: Vec3) -> Arc<PrimaryAutoCommandBuffer> {
+        let recording_start = Instant::now();
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            self.command_buffer_allocator.clone(),
+            self.logical_device.active_queue_family_indices()[0],
+            CommandBufferUsage::OneTimeSubmit
+        ).unwrap();
+
+        //Built once and culled separately for the camera and every shadow cascade.
+        let batches = self.build_draw_batches(camera_position);
+
+        let shadow_matrices = cascade_view_projections(&frustum_corners(view_projection), CAMERA_NEAR, CAMERA_FAR, self.directional_light.direction, &self.shadow_settings);
+        let shadow_draws = self.record_shadows(&mut builder, image_index, &batches, &shadow_matrices);
+
+        self.render_graph.begin_pass(&mut builder, "scene", image_index);
+        builder.set_viewport_with_count(self.viewports.clone()).unwrap();
+        builder.set_scissor_with_count(self.scissors.clone()).unwrap();
+        builder.push_constants(self.pipeline_layout.clone(), 0, PushConstants::new(view_projection)).unwrap();
+        builder.bind_descriptor_sets(
+            PipelineBindPoint::Graphics,
+            self.pipeline_layout.clone(),
+            0,
+            self.create_frame_descriptor_set(camera_position, image_index, &shadow_matrices),
+        ).unwrap();
+
+        let mut pipeline_binds = shadow_draws.min(1);
+        if let (true, Some(skybox_pipeline)) = (self.skybox_enabled, self.pipelines.get("skybox")) {
+            builder.bind_pipeline_graphics(skybox_pipeline.clone()).unwrap();
+            unsafe { builder.draw(3, 1, 0, 0).unwrap() };
+            pipeline_binds += 1;
+        }
+
+        let frustum = Frustum::from_view_projection(view_projection);
+        let mut visible_instances = vec![];
+        let visible_counts: Vec<usize> = batches.iter().map(|batch| batch.cull_into(&frustum, &mut visible_instances)).collect();
+        let instance_count = visible_instances.len();
+        let culled_objects = batches.iter().map(|batch| batch.instances.len()).sum::<usize>() - instance_count;
+        let mut draw_calls = shadow_draws;
+        let mut material_binds = 0;
+
+        if instance_count > 0 {
+            let instance_buffer = self.instance_buffer_allocator.allocate_slice::<InstanceData>(instance_count as u64).unwrap();
+            instance_buffer.write().unwrap().copy_from_slice(&visible_instances);
+
+            let mut first_instance: u64 = 0;
+            let mut bound_pipeline: Option<&str> = None;
+            let mut bound_material: Option<&MaterialHandle> = None;
+            for (batch, visible_count) in batches.iter().zip(visible_counts) {
+                if visible_count == 0 {
+                    continue;
+                }
+
+                let batch_instances = instance_buffer.clone().slice(first_instance..first_instance + visible_count as u64);
+                first_instance += visible_count as u64;
+
+                //Every pipeline shares one layout, so the push constants and descriptor sets stay bound when switching.
+                if bound_pipeline != Some(batch.pipeline) {
+                    builder.bind_pipeline_graphics(self.pipelines.get(batch.pipeline).unwrap().clone()).unwrap();
+                    bound_pipeline = Some(batch.pipeline);
+                    pipeline_binds += 1;
+                }
+
+                if bound_material != Some(batch.material.get_handle()) {
+                    builder.bind_descriptor_sets(
+                        PipelineBindPoint::Graphics,
+                        self.pipeline_layout.clone(),
+                        1,
+                        batch.material.get_descriptor_set(),
+                    ).unwrap();
+                    bound_material = Some(batch.material.get_handle());
+                    material_binds += 1;
+                }
+
+                builder.bind_vertex_buffers(0, (batch.mesh.get_vertex_buffer().clone(), batch_instances)).unwrap();
+                builder.bind_index_buffer(batch.mesh.get_index_buffer().clone()).unwrap();
+                unsafe { builder.draw_indexed(batch.mesh.get_index_count(), visible_count as u32, 0, 0, 0).unwrap() };
+                draw_calls += 1;
+            }
+        }
+
+        let debug_draws = self.record_debug_lines(&mut builder, &take_debug_lines(Instant::now()));
+        draw_calls += debug_draws;
+        pipeline_binds += debug_draws;
+
+        builder.end_render_pass(SubpassEndInfo::default()).unwrap();
+
+        let (post_processed, post_passes) = self.record_post_process(&mut builder, image_index);
+        draw_calls += post_passes;
+        pipeline_binds += post_passes;
+
+        //Text and UI go on top of the post processed scene, so they skip multisampling and never get depth tested against it.
+        self.render_graph.begin_pass(&mut builder, "overlay", image_index);
+
+        if self.record_post_output(&mut builder, post_processed) {
+            draw_calls += 1;
+            pipeline_binds += 1;
+        }
+        builder.set_viewport_with_count(self.viewports.clone()).unwrap();
+        builder.set_scissor_with_count(self.scissors.clone()).unwrap();
+
+        let text_sections = std::mem::take(&mut self.text_sections);
+        if self.record_text(&mut builder, &text_sections) {
+            draw_calls += 1;
+            pipeline_binds += 1;
+        }
+
+        let ui_frame = std::mem::take(&mut self.ui_frame);
+        let ui_draws = self.record_ui(&mut builder, &ui_frame);
+        draw_calls += ui_draws;
+        pipeline_binds += ui_draws.min(1);
+        for texture_id in ui_frame.textures_delta.free.iter() {
+            self.ui_textures.free(*texture_id);
+        }
+
+        builder.end_render_pass(SubpassEndInfo::default()).unwrap();
+        let command_buffer = builder.build().unwrap();
+
+        self.frame_stats = FrameStats {
+            objects: instance_count,
+            culled_objects,
+            draw_calls,
+            material_binds,
+            pipeline_binds,
+            recording_time: recording_start.elapsed(),
+        };
+        
+        return command_buffer;
+    }
+
+    //Draws every object that casts shadows into the shadow map, cascade by cascade into its own square and culled against the cascade. Returns the amount of draw calls.
+    fn record_shadows(&self, builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>, image_index: usize, batches: &[DrawBatch], shadow_matrices: &[Mat4]) -> usize {
+        self.render_graph.begin_pass(builder, "shadow", image_index);
+
+        let mut draw_calls = 0;
+        if let Some(shadow_pipeline) = self.pipelines.get("shadow") {
+            builder.bind_pipeline_graphics(shadow_pipeline.clone()).unwrap();
+
+            let casters: Vec<&DrawBatch> = batches.iter().filter(|batch| self.pipelines.casts_shadows(batch.pipeline)).collect();
+            let resolution = self.shadow_settings.resolution;
+            for (cascade, shadow_matrix) in shadow_matrices.iter().enumerate() {
+                let frustum = Frustum::from_view_projection(*shadow_matrix);
+                let mut visible_instances = vec![];
+                let visible_counts: Vec<usize> = casters.iter().map(|batch| batch.cull_into(&frustum, &mut visible_instances)).collect();
+                if visible_instances.is_empty() {
+                    continue;
+                }
+
+                let offset = [cascade as u32 * resolution, 0];
+                builder.set_viewport_with_count(smallvec![Viewport { offset: [offset[0] as f32, 0.0], extent: [resolution as f32, resolution as f32], depth_range: 0.0..=1.0 }]).unwrap();
+                builder.set_scissor_with_count(smallvec![Scissor { offset, extent: [resolution, resolution] }]).unwrap();
+                builder.push_constants(self.pipeline_layout.clone(), 0, PushConstants::new(*shadow_matrix)).unwrap();
+
+                let instance_buffer = self.instance_buffer_allocator.allocate_slice::<InstanceData>(visible_instances.len() as u64).unwrap();
+                instance_buffer.write().unwrap().copy_from_slice(&visible_instances);
+
+                let mut first_instance: u64 = 0;
+                for (batch, visible_count) in casters.iter().zip(visible_counts) {
+                    if visible_count == 0 {
+                        continue;
+                    }
+
+                    let batch_instances = instance_buffer.clone().slice(first_instance..first_instance + visible_count as u64);
+                    first_instance += visible_count as u64;
+
+                    builder.bind_vertex_buffers(0, (batch.mesh.get_vertex_buffer().clone(), batch_instances)).unwrap();
+                    builder.bind_index_buffer(batch.mesh.get_index_buffer().clone()).unwrap();
+                    unsafe { builder.draw_indexed(batch.mesh.get_index_count(), visible_count as u32, 0, 0, 0).unwrap() };
+                    draw_calls += 1;
+                }
+            }
+        }
+
+        builder.end_render_pass(SubpassEndInfo::default()).unwrap();
+        return draw_calls;
+    }
+
+    //Runs the chain on the HDR targets of the image, every effect reads the target the one before it wrote and writes the other.
+    //Effects whose pipelines or lookup table are missing are skipped. Returns the target holding the result and the amount of passes recorded.
+    fn record_post_process(&self, builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>, image_index: usize) -> (Arc<ImageView>, usize) {
+        let targets = HDR_TARGETS.map(|target| self.render_graph.get_attachment(target, image_index));
+        let mut source = 0;
+        let mut passes = 0;
+
+        for effect in self.post_process_chain.get_effects().iter() {
+            let Some(pipeline) = self.pipelines.get(effect.get_pipeline_name()) else {
+                continue;
+            };
+            let secondary = match effect {
+                PostProcessEffect::ColorGrading { lut_path } => match self.post_process_luts.get(lut_path) {
+                    Some(lut) => Some(lut.get_image_view()),
+                    None => continue,
+                },
+                PostProcessEffect::Bloom { threshold, .. } => match self.record_bloom(builder, image_index, targets[source].clone(), *threshold) {
+                    Some((bloom, bloom_passes)) => {
+                        passes += bloom_passes;
+                        Some(bloom)
+                    },
+                    None => continue,
+                },
+                _ => None,
+            };
+
+            self.record_fullscreen_pass(builder, POST_PASSES[1 - source], image_index, pipeline.clone(), targets[source].clone(), secondary, effect.get_parameters());
+            source = 1 - source;
+            passes += 1;
+        }
+
+        return (targets[source].clone(), passes);
+    }
+
+    //Thresholds the scene into the first bloom level, downsamples it level by level and upsamples back up, blurring a little more at every step.
+    //Returns the blurred bright parts at half resolution and the amount of passes recorded.
+    fn record_bloom(&self, builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>, image_index: usize, source: Arc<ImageView>, threshold: f32) -> Option<(Arc<ImageView>, usize)> {
+        let (Some(prefilter), Some(downsample), Some(upsample)) = (self.pipelines.get("post_bloom_prefilter"), self.pipelines.get("post_bloom_downsample"), self.pipelines.get("post_bloom_upsample")) else {
+            return None;
+        };
+        let levels = bloom_level_extents(self.get_present_extent()).len();
+        let downsampled = |level: usize| self.render_graph.get_attachment(&VulkanContainer::bloom_downsampled(level), image_index);
+        let upsampled = |level: usize| self.render_graph.get_attachment(&VulkanContainer::bloom_upsampled(level), image_index);
+
+        self.record_fullscreen_pass(builder, &VulkanContainer::bloom_downsampled(0), image_index, prefilter.clone(), source, None, Vec4::new(threshold, 0.0, 0.0, 0.0));
+        for level in 1..levels {
+            self.record_fullscreen_pass(builder, &VulkanContainer::bloom_downsampled(level), image_index, downsample.clone(), downsampled(level - 1), None, Vec4::ZERO);
+        }
+
+        //The smallest level has nothing below it to add, so the way back up starts from it as is.
+        let mut blurred = downsampled(levels - 1);
+        for level in (0..levels - 1).rev() {
+            self.record_fullscreen_pass(builder, &VulkanContainer::bloom_upsampled(level), image_index, upsample.clone(), blurred, Some(downsampled(level)), Vec4::ZERO);
+            blurred = upsampled(level);
+        }
+
+        return Some((blurred, levels * 2 - 1));
+    }
+
+    //A single full screen triangle covering the whole framebuffer, no matter where the viewport is.
+    fn record_fullscreen_pass(&self, builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>, pass: &str, image_index: usize, pipeline: Arc<GraphicsPipeline>, source: Arc<ImageView>, secondary: Option<Arc<ImageView>>, parameters: Vec4) {
+        let extent = self.render_graph.get_framebuffer(pass, image_index).extent();
+
+        self.render_graph.begin_pass(builder, pass, image_index);
+        builder.set_viewport_with_count(smallvec![Viewport { offset: [0.0, 0.0], extent: [extent[0] as f32, extent[1] as f32], depth_range: 0.0..=1.0 }]).unwrap();
+        builder.set_scissor_with_count(smallvec![Scissor { offset: [0, 0], extent }]).unwrap();
+        builder.bind_pipeline_graphics(pipeline).unwrap();
+        builder.bind_descriptor_sets(
+            PipelineBindPoint::Graphics,
+            self.pipeline_layout.clone(),
+            2,
+            self.create_post_process_descriptor_set(source, secondary, parameters),
+        ).unwrap();
+        unsafe { builder.draw(3, 1, 0, 0).unwrap() };
+        builder.end_render_pass(SubpassEndInfo::default()).unwrap();
+    }
+
+    //Copies the end of the chain into the presented image pixel for pixel, has to be recorded first thing inside the overlay pass.
+    fn record_post_output(&self, builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>, post_processed: Arc<ImageView>) -> bool {
+        let Some(pipeline) = self.pipelines.get("post_output") else {
+            return false;
+        };
+
+        let [width, height, _] = post_processed.image().extent();
+        builder.set_viewport_with_count(smallvec![Viewport { offset: [0.0, 0.0], extent: [width as f32, height as f32], depth_range: 0.0..=1.0 }]).unwrap();
+        builder.set_scissor_with_count(smallvec![Scissor { offset: [0, 0], extent: [width, height] }]).unwrap();
+        builder.bind_pipeline_graphics(pipeline.clone()).unwrap();
+        builder.bind_descriptor_sets(
+            PipelineBindPoint::Graphics,
+            self.pipeline_layout.clone(),
+            2,
+            self.create_post_process_descriptor_set(post_processed, None, Vec4::ZERO),
+        ).unwrap();
+        unsafe { builder.draw(3, 1, 0, 0).unwrap() };
+
+        return true;
+    }
+
+    //Every binding has to be written, passes without a second image get their source bound in its place.
+    fn create_post_process_descriptor_set(&self, source: Arc<ImageView>, lut: Option<Arc<ImageView>>, parameters: Vec4) -> Arc<DescriptorSet> {
+        let [width, height, _] = source.image().extent();
+        let uniform_buffer = self.uniform_buffer_allocator.allocate_sized::<PostProcessUniforms>().unwrap();
+        *uniform_buffer.write().unwrap() = PostProcessUniforms::new(parameters, [width, height]);
+
+        return DescriptorSet::new(
+            self.descriptor_set_allocator.clone(),
+            self.pipeline_layout.set_layouts().get(2).unwrap().clone(),
+            [
+                WriteDescriptorSet::image_view_sampler(0, source.clone(), self.post_sampler.clone()),
+                WriteDescriptorSet::image_view_sampler(1, lut.unwrap_or(source), self.post_sampler.clone()),
+                WriteDescriptorSet::buffer(2, uniform_buffer),
+            ],
+            [],
+        ).unwrap();
+    }
+
+    //Depth tested lines go first, then the ones drawn on top of everything. Each group is a single draw, the amount of draws is returned.
+    fn record_debug_lines(&self, builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>, debug_lines: &[DebugLine]) -> usize {
+        let mut draws = 0;
+        for (pipeline_name, depth_test) in [("debug_lines", true), ("debug_lines_overlay", false)] {
+            let vertices: Vec<DebugVertex> = debug_lines.iter()
+                .filter(|line| line.depth_test == depth_test)
+                .flat_map(|line| [DebugVertex::new(line.start, line.color), DebugVertex::new(line.end, line.color)])
+                .collect();
+
+            let Some(pipeline) = self.pipelines.get(pipeline_name) else {
+                continue;
+            };
+            if vertices.is_empty() {
+                continue;
+            }
+
+            let vertex_buffer = self.instance_buffer_allocator.allocate_slice::<DebugVertex>(vertices.len() as u64).unwrap();
+            vertex_buffer.write().unwrap().copy_from_slice(&vertices);
+
+            builder.bind_pipeline_graphics(pipeline.clone()).unwrap();
+            builder.bind_vertex_buffers(0, vertex_buffer).unwrap();
+            unsafe { builder.draw(vertices.len() as u32, 1, 0, 0).unwrap() };
+            draws += 1;
+        }
+
+        return draws;
+    }
+
+    //Every queued text section goes into one vertex buffer and a single draw on top of the scene, returns whether anything was drawn.
+    fn record_text(&self, builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>, text_sections: &[TextSection]) -> bool {
+        let (Some(font), Some(pipeline)) = (self.font.as_ref(), self.pipelines.get("text")) else {
+            return false;
+        };
+
+        //Vulkan puts -1 at the top of the viewport, so pixels only need scaling and shifting.
+        let extent = vec2(self.viewports[0].extent[0], self.viewports[0].extent[1]);
+        let to_ndc = |pixel: Vec2| pixel / extent * 2.0 - Vec2::ONE;
+
+        let mut vertices = vec![];
+        for section in text_sections.iter() {
+            for quad in font.get_atlas().layout(&section.text, section.position, section.scale) {
+                let corner = |x: bool, y: bool| TextVertex::new(
+                    to_ndc(vec2(if x { quad.max.x } else { quad.min.x }, if y { quad.max.y } else { quad.min.y })),
+                    vec2(if x { quad.uv_max.x } else { quad.uv_min.x }, if y { quad.uv_max.y } else { quad.uv_min.y }),
+                    section.color,
+                );
+                vertices.extend([corner(false, false), corner(true, false), corner(true, true), corner(false, false), corner(true, true), corner(false, true)]);
+            }
+        }
+
+        if vertices.is_empty() {
+            return false;
+        }
+
+        let vertex_buffer = self.instance_buffer_allocator.allocate_slice::<TextVertex>(vertices.len() as u64).unwrap();
+        vertex_buffer.write().unwrap().copy_from_slice(&vertices);
+
+        builder.bind_pipeline_graphics(pipeline.clone()).unwrap();
+        builder.bind_descriptor_sets(
+            PipelineBindPoint::Graphics,
+            self.pipeline_layout.clone(),
+            1,
+            font.get_descriptor_set(),
+        ).unwrap();
+        builder.bind_vertex_buffers(0, vertex_buffer).unwrap();
+        unsafe { builder.draw(vertices.len() as u32, 1, 0, 0).unwrap() };
+
+        return true;
+    }
+
+    //All UI meshes share one vertex and one index buffer, every mesh is its own draw with its own scissor. Returns the amount of draws.
+    fn record_ui(&self, builder: &mut AutoCommandBufferBuilder<PrimaryAutoCommandBuffer>, ui_frame: &UiFrame) -> usize {
+        let Some(pipeline) = self.pipelines.get("ui") else {
+            return 0;
+        };
+
+        let extent = [self.viewports[0].extent[0] as u32, self.viewports[0].extent[1] as u32];
+        let (vertices, indices, draws) = build_ui_draws(&ui_frame.primitives, ui_frame.pixels_per_point, extent);
+        if draws.is_empty() {
+            return 0;
+        }
+
+        let vertex_buffer = self.instance_buffer_allocator.allocate_slice::<UiVertex>(vertices.len() as u64).unwrap();
+        vertex_buffer.write().unwrap().copy_from_slice(&vertices);
+        let index_buffer = self.instance_buffer_allocator.allocate_slice::<u32>(indices.len() as u64).unwrap();
+        index_buffer.write().unwrap().copy_from_slice(&indices);
+
+        //egui lays out in points with the origin in the top left corner.
+        let screen_size = vec2(self.viewports[0].extent[0], self.viewports[0].extent[1]) / ui_frame.pixels_per_point;
+        let points_to_clip = Mat4::orthographic_rh(0.0, screen_size.x, 0.0, screen_size.y, -1.0, 1.0);
+
+        builder.bind_pipeline_graphics(pipeline.clone()).unwrap();
+        builder.push_constants(self.pipeline_layout.clone(), 0, PushConstants::new(points_to_clip)).unwrap();
+        builder.bind_vertex_buffers(0, vertex_buffer).unwrap();
+        builder.bind_index_buffer(index_buffer).unwrap();
+
+        let mut draw_count = 0;
+        for draw in draws.iter() {
+            let Some(descriptor_set) = self.ui_textures.get_descriptor_set(draw.texture) else {
+                continue;
+            };
+
+            builder.bind_descriptor_sets(PipelineBindPoint::Graphics, self.pipeline_layout.clone(), 1, descriptor_set).unwrap();
+            builder.set_scissor_with_count(smallvec![draw.scissor]).unwrap();
+            unsafe { builder.draw_indexed(draw.index_count, 1, draw.first_index, draw.vertex_offset, 0).unwrap() };
+            draw_count += 1;
+        }
+
+        return draw_count;
+    }
+
+    //The uniform buffer comes from a subbuffer allocator, so every frame in flight writes its own copy and never touches one the GPU still reads.
+    //The environment map sits next to the frame data so the skybox and reflective materials sample the same cubemap.
+    fn create_frame_descriptor_set(&self, camera_position: Vec3, image_index: usize, shadow_matrices: &[Mat4]) -> Arc<DescriptorSet> {
+        let uniform_buffer = self.uniform_buffer_allocator.allocate_sized::<UniformBufferObject>().unwrap();
+        *uniform_buffer.write().unwrap() = UniformBufferObject::new(self.ambient_light, camera_position, &self.directional_light, self.point_lights.values()).with_shadows(shadow_matrices, &self.shadow_settings);
+
+        return DescriptorSet::new(
+            self.descriptor_set_allocator.clone(),
+            self.pipeline_layout.set_layouts().get(0).unwrap().clone(),
+            [
+                WriteDescriptorSet::buffer(0, uniform_buffer),
+                WriteDescriptorSet::image_view_sampler(1, self.environment_map.clone(), self.environment_sampler.clone()),
+                WriteDescriptorSet::image_view_sampler(2, self.render_graph.get_attachment("shadow_map", image_index), self.shadow_sampler.clone()),
+            ],
+            [],
+        ).unwrap();
+    }
+
+    //Objects sharing a mesh and material end up in the same batch and get drawn with a single instanced draw call.
+    //With instancing turned off every object becomes its own batch, which is only useful to compare against.
+    //Batches are sorted by pipeline and then by material so every pipeline and material only has to be bound once per frame.
+    //Objects using a transparent pipeline come after all of that, one batch per object ordered from furthest to closest to the camera so blending stacks up correctly.
+    //Nothing is culled yet, the batches are shared by the camera and the shadow cascades which each cull them against their own frustum.
+    fn build_draw_batches(&self, camera_position: Vec3) -> Vec<DrawBatch<'_>> {
+        let mut batches: Vec<DrawBatch> = vec![];
+        let mut transparent_batches: Vec<(f32, DrawBatch)> = vec![];
+        let mut batch_indices: HashMap<(&str, &MaterialHandle, *const GpuMesh), usize> = HashMap::new();
+        let default_material = &self.materials[&MaterialHandle::default()];
+
+        for vulkan_object in self.vulkan_objects.values() {
+            let bounds = *vulkan_object.get_bounds();
+            let instance = InstanceData::new(vulkan_object.get_transform().to_matrix());
+            let material = self.materials.get(vulkan_object.get_material()).unwrap_or(default_material);
+            let pipeline = if self.pipelines.is_mesh_pipeline(material.get_pipeline()) { material.get_pipeline() } else { DEFAULT_PIPELINE };
+
+            if self.pipelines.is_transparent(pipeline) {
+                let distance = bounds.center.distance_squared(camera_position);
+                transparent_batches.push((distance, DrawBatch {
+                    pipeline,
+                    mesh: vulkan_object.get_mesh(),
+                    material,
+                    bounds: vec![bounds],
+                    instances: vec![instance],
+                }));
+                continue;
+            }
+
+            if self.instancing {
+                let key = (pipeline, material.get_handle(), Arc::as_ptr(vulkan_object.get_mesh()));
+                if let Some(batch_index) = batch_indices.get(&key) {
+                    batches[*batch_index].bounds.push(bounds);
+                    batches[*batch_index].instances.push(instance);
+                    continue;
+                }
+                batch_indices.insert(key, batches.len());
+            }
+
+            batches.push(DrawBatch {
+                pipeline,
+                mesh: vulkan_object.get_mesh(),
+                material,
+                bounds: vec![bounds],
+                instances: vec![instance],
+            });
+        }
+
+        batches.sort_by(|a, b| a.pipeline.cmp(b.pipeline).then_with(|| a.material.get_handle().cmp(b.material.get_handle())).then_with(|| a.mesh.get_name().cmp(b.mesh.get_name())));
+
+        transparent_batches.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+        batches.extend(transparent_batches.into_iter().map(|(_, batch)| batch));
+
+        return batches;
+    }
+
+    //Replaces the skybox and the environment map reflective materials see, None goes back to a black background.
+    //A skybox that fails to load is logged and the previous one stays.
+    pub fn set_skybox(&mut self, source: Option<&SkyboxSource>) {
+        Logger::log(LogLevel::High, "vulkan_wrapper", "Setting skybox...");
+
+        let cubemap = match source.map(CubemapPixels::load) {
+            Some(Ok(cubemap)) => cubemap,
+            Some(Err(e)) => {
+                Logger::log(LogLevel::High, "vulkan_wrapper", &format!("Failed to load skybox {:?}: {}", source.unwrap(), e));
+                return;
+            },
+            None => CubemapPixels::solid(Vec4::new(0.0, 0.0, 0.0, 1.0)),
+        };
+
+        match VulkanContainer::upload_cubemap(self.logical_device.clone(), self.queue.clone(), self.memory_allocator.clone(), self.command_buffer_allocator.clone(), &cubemap) {
+            Ok(environment_map) => {
+                self.environment_map = environment_map;
+                self.skybox_enabled = source.is_some();
+                Logger::log(LogLevel::High, "vulkan_wrapper", "Skybox set successfully.");
+            },
+            Err(e) => Logger::log(LogLevel::High, "vulkan_wrapper", &format!("Failed to upload skybox: {}", e)),
+        }
+    }
+
+    //Lookup tables of the new chain are loaded before the old ones are released, so tables used by both stay loaded. Color grading with a table that fails to load is skipped.
+    pub fn set_post_process_chain(&mut self, post_process_chain: PostProcessChain) {
+        let mut post_process_luts = HashMap::new();
+        for lut_path in post_process_chain.get_effects().iter().filter_map(|effect| effect.get_lut_path()) {
+            if post_process_luts.contains_key(lut_path) {
+                continue;
+            }
+
+            match self.acquire_texture(Some(lut_path)) {
+                Ok(lut) => { post_process_luts.insert(lut_path.to_string(), lut); },
+                Err(e) => Logger::log(LogLevel::High, "vulkan_wrapper", &format!("Failed to load lookup table {}, color grading with it is skipped: {}", lut_path, e)),
+            }
+        }
+
+        for lut_path in std::mem::replace(&mut self.post_process_luts, post_process_luts).into_keys() {
+            self.release_texture(&lut_path);
+        }
+        self.post_process_chain = post_process_chain;
+    }
+
+    //The shadow map changes size with the resolution and the amount of cascades, so the render graph is rebuilt. The bias only goes into the frame uniforms.
+    pub fn set_shadow_settings(&mut self, shadow_settings: ShadowSettings) {
+        let shadow_settings = VulkanContainer::fit_shadow_settings(&self.physical_device, shadow_settings);
+        let resized = shadow_settings.resolution != self.shadow_settings.resolution || shadow_settings.cascades != self.shadow_settings.cascades;
+
+        self.shadow_settings = shadow_settings;
+        if resized {
+            self.rebuild_render_graph();
+        }
+    }
+
+    //Rebakes the glyph atlas, text keeps using the previous font if the new one can't be loaded.
+    pub fn set_font(&mut self, font_path: &str, pixel_size: f32) {
+        Logger::log(LogLevel::High, "vulkan_wrapper", &format!("Loading font {} at {} pixels...", font_path, pixel_size));
+
+        match self.create_font(font_path, pixel_size) {
+            Ok(font) => {
+                self.font = Some(font);
+                Logger::log(LogLevel::High, "vulkan_wrapper", "Font loaded successfully.");
+            },
+            Err(e) => Logger::log(LogLevel::High, "vulkan_wrapper", &format!("Failed to load font {}: {}", font_path, e)),
+        }
+    }
+
+    //The atlas is bound through the material set layout so the text pipeline can share the pipeline layout with everything else.
+    fn create_font(&mut self, font_path: &str, pixel_size: f32) -> Result<GpuFont, Box<dyn std::error::Error>> {
+        let atlas = GlyphAtlas::from_font_file(font_path, pixel_size)?;
+        let atlas_view = self.upload_texture(atlas.get_width(), atlas.get_height(), atlas.to_rgba())?;
+
+        let sampler = Sampler::new(
+            self.logical_device.clone(),
+            SamplerCreateInfo {
+                mag_filter: Filter::Linear,
+                min_filter: Filter::Linear,
+                address_mode: [SamplerAddressMode::ClampToEdge; 3],
+                ..Default::default()
+            },
+        )?;
+
+        return Ok(GpuFont::new(atlas, self.create_texture_descriptor_set(atlas_view, sampler)?));
+    }
+
+    //A material set for things that only need a texture, like fonts and UI images. The uniforms are left at the default material's.
+    fn create_texture_descriptor_set(&self, image_view: Arc<ImageView>, sampler: Arc<Sampler>) -> Result<Arc<DescriptorSet>, Box<dyn std::error::Error>> {
+        let material_buffer = Buffer::from_data(
+            self.memory_allocator.clone(),
+            BufferCreateInfo {
+                usage: BufferUsage::UNIFORM_BUFFER,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_DEVICE | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            },
+            MaterialUniforms::new(&Material::default()),
+        )?;
+
+        return Ok(DescriptorSet::new(
+            self.descriptor_set_allocator.clone(),
+            self.pipeline_layout.set_layouts().get(1).unwrap().clone(),
+            [
+                WriteDescriptorSet::image_view_sampler(0, image_view, sampler),
+                WriteDescriptorSet::buffer(1, material_buffer),
+            ],
+            [],
+        )?);
+    }
+
+    //Takes the UI for the next frame. Changed textures are uploaded right away, a texture that fails to upload is left out of the UI.
+    pub fn set_ui_frame(&mut self, ui_frame: UiFrame) {
+        for (texture_id, delta) in ui_frame.textures_delta.set.iter() {
+            self.ui_textures.apply(*texture_id, delta);
+        }
+
+        for texture_id in self.ui_textures.pending_uploads() {
+            let (Some((width, height, rgba)), Some(sampler_create_info)) = (self.ui_textures.get_rgba(texture_id), self.ui_textures.get_sampler_create_info(texture_id)) else {
+                continue;
+            };
+
+            let uploaded = self.upload_texture(width, height, rgba)
+                .and_then(|image_view| Ok((image_view, Sampler::new(self.logical_device.clone(), sampler_create_info)?)))
+                .and_then(|(image_view, sampler)| self.create_texture_descriptor_set(image_view, sampler));
+            match uploaded {
+                Ok(descriptor_set) => self.ui_textures.set_descriptor_set(texture_id, descriptor_set),
+                Err(e) => {
+                    Logger::log(LogLevel::High, "vulkan_wrapper", &format!("Failed to upload UI texture {:?}: {}", texture_id, e));
+                    self.ui_textures.free(texture_id);
+                },
+            }
+        }
+
+        self.ui_frame = ui_frame;
+    }
+
+    //Queues text for the next frame only, anything that should stay on screen has to be queued again every frame.
+    pub fn draw_text(&mut self, text_section: TextSection) {
+        self.text_sections.push(text_section);
+    }
+
+    pub fn set_ambient_light(&mut self, ambient_light: Vec3) {
+        self.ambient_light = ambient_light;
+    }
+
+    pub fn set_directional_light(&mut self, directional_light: DirectionalLight) {
+        self.directional_light = directional_light;
+    }
+
+    pub fn create_point_light(&mut self, id: usize, point_light: PointLight) {
+        self.point_lights.insert(id, point_light);
+        if self.point_lights.len() > MAX_POINT_LIGHTS {
+            Logger::log(LogLevel::High, "vulkan_wrapper", &format!("{} point lights exist but only {} are lit, the ones with the highest IDs are ignored.", self.point_lights.len(), MAX_POINT_LIGHTS));
+        }
+    }
+
+    pub fn delete_point_light(&mut self, id: usize) {
+        if self.point_lights.remove(&id).is_none() {
+            Logger::log(LogLevel::High, "vulkan_wrapper", &format!("No point light with ID {} to delete.", id));
+        }
+    }
+
+    pub fn set_instancing(&mut self, instancing: bool) {
+        self.instancing = instancing;
+    }
+
+    pub fn get_frame_stats(&self) -> &FrameStats {
+        return &self.frame_stats;
+    }
+
+    pub fn draw_frame(&mut self, camera_transform: &Transform) {
+        self.reload_changed_shaders();
+
+        let view_projection = VulkanContainer::make_view_projection(self.viewports[0].extent[0] as f32 / self.viewports[0].extent[1] as f32, camera_transform);
+
+        if self.swapchain.is_none() {
+            //Kept around so reading the frame back fails instead of handing out whatever was left in the image.
+            self.offscreen_frame_error = match self.draw_offscreen_frame(view_projection, camera_transform.get_camera_position()) {
+                Ok(()) => None,
+                Err(e) => {
+                    Logger::log(LogLevel::High, "vulkan_wrapper", &format!("Failed to draw offscreen frame: {}", e));
+                    Some(e.to_string())
+                },
+            };
+            return;
+        }
+
+        //The resources of this slot may still be in use by the frame submitted the last time around.
+        let frame_index = self.current_frame;
+        self.frames_in_flight[frame_index].wait();
+
+        if self.swapchain_outdated {
+            self.recreate_swapchain();
+            if self.swapchain_outdated {
+                return;
+            }
+        }
+
+        //Only bound once the swapchain is up to date, so the image is acquired from and presented to the same one.
+        let swapchain = self.swapchain.clone().unwrap();
+
+        let (image_index, suboptimal, acquire_future) = match swapchain::acquire_next_image(swapchain.clone(), None).map_err(Validated::unwrap) {
+            Ok(acquired) => acquired,
+            Err(VulkanError::OutOfDate) => {
+                self.swapchain_outdated = true;
+                return;
+            },
+            Err(e) => panic!("Failed to acquire next swapchain image: {}", e),
+        };
+
+        if suboptimal {
+            self.swapchain_outdated = true;
+        }
+
+        let command_buffer = self.create_command_buffer(image_index.try_into().unwrap(), view_projection, camera_transform.get_camera_position());
+
+        //Chaining onto the previous frame lets vulkano know about the resources it still uses, without waiting for it.
+        let previous_future: Box<dyn GpuFuture> = match self.frames_in_flight[self.previous_frame].get_fence() {
+            Some(fence) => fence.boxed(),
+            None => {
+                let mut now = sync::now(self.logical_device.clone());
+                now.cleanup_finished();
+                now.boxed()
+            },
+        };
+
+        let future = previous_future
+            .join(acquire_future)
+            .then_execute(self.queue.clone(), command_buffer)
+            .unwrap()
+            .then_swapchain_present(
                 self.queue.clone(),
-                SwapchainPresentInfo::swapchain_image_index(self.swapchain.clone(),
-                image_index
+                SwapchainPresentInfo::swapchain_image_index(swapchain, image_index),
             )
-        ));
-
-        match future {
-            Ok(fut) => { fut.wait(None).unwrap(); }
+            .boxed()
+            .then_signal_fence_and_flush();
+
+        let fence = match future.map_err(Validated::unwrap) {
+            Ok(fence) => Some(Arc::new(fence)),
+            Err(VulkanError::OutOfDate) => {
+                self.swapchain_outdated = true;
+                None
+            },
             Err(e) => {
-                eprintln!("Failed to flush frame: {:?}", e);
-            }
+                Logger::log(LogLevel::High, "vulkan_wrapper", &format!("Failed to flush frame: {:?}", e));
+                None
+            },
+        };
+
+        self.frames_in_flight[frame_index].set_fence(fence);
+        self.previous_frame = frame_index;
+        self.current_frame = (frame_index + 1) % self.frames_in_flight.len();
+    }
+
+    fn draw_offscreen_frame(&mut self, view_projection: Mat4, camera_position: Vec3) -> Result<(), Box<dyn std::error::Error>> {
+        let command_buffer = self.create_command_buffer(0, view_projection, camera_position);
+        let future = sync::now(self.logical_device.clone())
+            .then_execute(self.queue.clone(), command_buffer)?
+            .then_signal_fence_and_flush()?;
+
+        future.wait(None)?;
+        Ok(())
+    }
+
+    //Copies the last offscreen frame back to the CPU. Only headless containers own their color image, windowed ones hand it to the swapchain.
+    pub fn read_frame(&self) -> Result<image::RgbaImage, Box<dyn std::error::Error>> {
+        if self.swapchain.is_some() {
+            return Err("Frames can only be read back from a headless container".into());
+        }
+
+        if let Some(error) = &self.offscreen_frame_error {
+            return Err(format!("The last frame failed to draw: {}", error).into());
         }
+
+        let image = self.images[0].clone();
+        let [width, height, _] = image.extent();
+
+        let readback_buffer = Buffer::new_slice::<u8>(
+            self.memory_allocator.clone(),
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_DST,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST | MemoryTypeFilter::HOST_RANDOM_ACCESS,
+                ..Default::default()
+            },
+            (width * height * 4) as u64,
+        )?;
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            self.command_buffer_allocator.clone(),
+            self.queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )?;
+
+        builder.copy_image_to_buffer(CopyImageToBufferInfo::image_buffer(
+            image,
+            readback_buffer.clone(),
+        ))?;
+
+        let command_buffer = builder.build()?;
+
+        let future = sync::now(self.logical_device.clone())
+            .then_execute(self.queue.clone(), command_buffer)?
+            .then_signal_fence_and_flush()?;
+
+        future.wait(None)?;
+
+        let pixels = readback_buffer.read()?.to_vec();
+        let frame = image::RgbaImage::from_raw(width, height, pixels).ok_or("Readback buffer does not match the frame size")?;
+
+        Ok(frame)
+    }
+
+    pub fn save_frame_png(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        Logger::log(LogLevel::Medium, "vulkan_wrapper", &format!("Saving frame to {}...", path));
+
+        self.read_frame()?.save_with_format(path, image::ImageFormat::Png)?;
+
+        Logger::log(LogLevel::Medium, "vulkan_wrapper", "Frame saved successfully.");
+        Ok(())
     }
 
     pub fn resize_viewport(&mut self, viewport_info: &ViewportInfo) {
@@ -573,36 +1822,81 @@ impl VulkanContainer {
             extent: [viewport_info.extent[0] as u32, viewport_info.extent[1] as u32],
         };
 
-        self.surface = VulkanContainer::create_surface(&self.instance.clone(), self.window.clone());
-        (self.swapchain, self.images) = self.swapchain.recreate(VulkanContainer::prepare_swapchain_create_info(self.physical_device.clone(), self.surface.clone(), self.window.clone())).unwrap();
-        self.image_views = VulkanContainer::create_image_views(&self.images.clone());
-        self.render_pass = VulkanContainer::create_render_pass(self.logical_device.clone(), self.swapchain.clone());
-        self.framebuffers = VulkanContainer::create_frame_buffers(self.render_pass.clone(), self.image_views.clone(), self.memory_allocator.clone());
+        //Windowed containers rebuild their swapchain lazily on the next frame, which also covers out of date swapchains the window never told us about.
+        if self.swapchain.is_some() {
+            self.swapchain_outdated = true;
+        } else {
+            self.images = vec![VulkanContainer::create_offscreen_image(self.memory_allocator.clone(), viewport_info)];
+            self.rebuild_render_graph();
+        }
 
         Logger::log(LogLevel::Medium, "vulkan_wrapper", "Viewport resized successfully.");
     }
 
-    fn make_view_projection(aspect_ratio: f32, camera_location: Vec3, camera_rotation: Vec3) -> Mat4 {
-        let rotation_x = Mat4::from_rotation_x(camera_rotation.x);
-        let rotation_y = Mat4::from_rotation_y(camera_rotation.y);
-        let rotation_z = Mat4::from_rotation_z(camera_rotation.z);
-        let rotation = rotation_x * rotation_y * rotation_z;
+    fn recreate_swapchain(&mut self) {
+        let (Some(swapchain), Some(window)) = (self.swapchain.clone(), self.window.clone()) else {
+            return;
+        };
+
+        //A minimized window has no size to create a swapchain for, keep the old one until it comes back.
+        let window_size = window.inner_size();
+        if window_size.width == 0 || window_size.height == 0 {
+            return;
+        }
+
+        Logger::log(LogLevel::Medium, "vulkan_wrapper", "Recreating swapchain...");
+
+        let (new_swapchain, new_images) = match swapchain.recreate(VulkanContainer::prepare_swapchain_create_info(self.physical_device.clone(), self.surface.clone().unwrap(), window)).map_err(Validated::unwrap) {
+            Ok(recreated) => recreated,
+            Err(VulkanError::OutOfDate) => return,
+            Err(e) => panic!("Failed to recreate swapchain: {}", e),
+        };
+
+        self.swapchain = Some(new_swapchain);
+        self.images = new_images;
+        self.rebuild_render_graph();
+        self.swapchain_outdated = false;
+
+        Logger::log(LogLevel::Medium, "vulkan_wrapper", "Swapchain recreated successfully.");
+    }
+
+    //Clamps the cascades and makes sure they all fit side by side into the largest image the device supports.
+    fn fit_shadow_settings(physical_device: &PhysicalDevice, shadow_settings: ShadowSettings) -> ShadowSettings {
+        let shadow_settings = ShadowSettings::new(shadow_settings.resolution, shadow_settings.bias, shadow_settings.cascades, shadow_settings.distance);
+        let max_resolution = physical_device.properties().max_image_dimension2_d / shadow_settings.cascades as u32;
+
+        return ShadowSettings { resolution: shadow_settings.resolution.min(max_resolution), ..shadow_settings };
+    }
+
+    //The graph is built again from scratch for the new images, the pipelines stay valid since its render passes are always compatible with the old ones.
+    fn rebuild_render_graph(&mut self) {
+        self.image_views = VulkanContainer::create_image_views(&self.images.clone());
+        self.render_graph = VulkanContainer::create_render_graph(self.logical_device.clone(), self.memory_allocator.clone(), &self.image_views, self.sample_count, &self.shadow_settings);
+    }
+
+    fn get_present_extent(&self) -> [u32; 2] {
+        let [width, height, _] = self.images[0].extent();
+        return [width, height];
+    }
 
-        let translation = Mat4::from_translation(camera_location);
-        let view = rotation * translation;
-        let proj = Mat4::perspective_rh_gl(45.0_f32.to_radians(), aspect_ratio, 0.1, 1000.0);
+    fn make_view_projection(aspect_ratio: f32, camera_transform: &Transform) -> Mat4 {
+        let view = camera_transform.to_view_matrix();
+        let proj = Mat4::perspective_rh_gl(CAMERA_FOV_Y_DEGREES.to_radians(), aspect_ratio, CAMERA_NEAR, CAMERA_FAR);
         let view_projection = proj * view;
 
         return view_projection;
     }
 
     //[TO-DO]: Almost entirely AI generated so I'll need to look this over some time to remake it properly.
-    fn load_png_texture(&mut self, path: &str) -> Result<(Arc<ImageView>, Arc<Sampler>), Box<dyn std::error::Error>> {
+    fn load_png_texture(&mut self, path: &str) -> Result<Arc<ImageView>, Box<dyn std::error::Error>> {
         // Load PNG file
         let img = image::open(path)?.to_rgba8();
         let (width, height) = img.dimensions();
-        let image_data = img.into_raw();
 
+        return self.upload_texture(width, height, img.into_raw());
+    }
+
+    fn upload_texture(&mut self, width: u32, height: u32, image_data: Vec<u8>) -> Result<Arc<ImageView>, Box<dyn std::error::Error>> {
         // Create staging buffer with the image data
         let staging_buffer = Buffer::from_iter(
             self.memory_allocator.clone(),
@@ -657,17 +1951,73 @@ impl VulkanContainer {
             ImageViewCreateInfo::from_image(&image),
         )?;
 
-        // Create sampler
-        let texture_sampler = Sampler::new(
-            self.logical_device.clone(),
-            SamplerCreateInfo {
-                mag_filter: Filter::Linear,
-                min_filter: Filter::Linear,
-                address_mode: [SamplerAddressMode::Repeat; 3],
+        Ok(texture_view)
+    }
+
+    //Cubemaps are stored as half floats so HDR skyboxes keep their bright values.
+    fn upload_cubemap(logical_device: Arc<Device>, queue: Arc<Queue>, memory_allocator: Arc<StandardMemoryAllocator>, command_buffer_allocator: Arc<StandardCommandBufferAllocator>, cubemap: &CubemapPixels) -> Result<Arc<ImageView>, Box<dyn std::error::Error>> {
+        let staging_buffer = Buffer::from_iter(
+            memory_allocator.clone(),
+            BufferCreateInfo {
+                usage: BufferUsage::TRANSFER_SRC,
+                ..Default::default()
+            },
+            AllocationCreateInfo {
+                memory_type_filter: MemoryTypeFilter::PREFER_HOST | MemoryTypeFilter::HOST_SEQUENTIAL_WRITE,
+                ..Default::default()
+            },
+            cubemap.get_texels().iter().flat_map(|texel| texel.to_array()).map(|value| f16::from_f32(value).to_bits()).collect::<Vec<u16>>(),
+        )?;
+
+        //Each of the six array layers is one face, in the same order as the texels.
+        let image = Image::new(
+            memory_allocator.clone(),
+            ImageCreateInfo {
+                flags: ImageCreateFlags::CUBE_COMPATIBLE,
+                image_type: ImageType::Dim2d,
+                format: Format::R16G16B16A16_SFLOAT,
+                extent: [cubemap.get_size(), cubemap.get_size(), 1],
+                array_layers: 6,
+                usage: ImageUsage::TRANSFER_DST | ImageUsage::SAMPLED,
                 ..Default::default()
             },
+            AllocationCreateInfo::default(),
+        )?;
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
         )?;
 
-        Ok((texture_view, texture_sampler))
+        builder.copy_buffer_to_image(CopyBufferToImageInfo::buffer_image(
+            staging_buffer,
+            image.clone(),
+        ))?;
+
+        let command_buffer = builder.build()?;
+
+        let future = sync::now(logical_device)
+            .then_execute(queue, command_buffer)?
+            .then_signal_fence_and_flush()?;
+
+        future.wait(None)?;
+
+        let cubemap_view = ImageView::new(
+            image.clone(),
+            ImageViewCreateInfo {
+                view_type: ImageViewType::Cube,
+                ..ImageViewCreateInfo::from_image(&image)
+            },
+        )?;
+
+        return Ok(cubemap_view);
+    }
+}
+
+//Whatever went into the pipeline cache since it was last saved is written out when the container goes away, so nothing built late in a session is lost.
+impl Drop for VulkanContainer {
+    fn drop(&mut self) {
+        self.save_pipeline_cache();
     }
 }
\ No newline at end of file
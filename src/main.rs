@@ -1,9 +1,33 @@
-use engine::utils::logger::{LogLevel, Logger};
+use std::time::Duration;
+
+use engine::{app::App, utils::{logger::{LogLevel, Logger}, structs::{material::MaterialHandle, mesh::Mesh, transform::Transform}}, vulkan::{structs::{render_settings::RenderSettings, viewport::ViewportInfo}, vulkan_container::VulkanContainer}};
+use glam::{vec3, Vec3};
 use winit::event_loop::EventLoop;
 
 mod engine;
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    //Usage: --headless <output.png> [width] [height]
+    if args.len() > 2 && args[1] == "--headless" {
+        let width = args.get(3).and_then(|width| width.parse::<f32>().ok()).unwrap_or(800.0);
+        let height = args.get(4).and_then(|height| height.parse::<f32>().ok()).unwrap_or(600.0);
+        //So CI rendering on a software driver fails instead of going green without an image.
+        if let Err(error) = run_headless(&args[2], [width, height]) {
+            Logger::log(LogLevel::High, "main", &format!("Headless render failed: {}", error));
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    //Usage: --bench-instancing [cube count]
+    if args.len() > 1 && args[1] == "--bench-instancing" {
+        let cube_count = args.get(2).and_then(|count| count.parse::<usize>().ok()).unwrap_or(10_000);
+        run_instancing_benchmark(cube_count);
+        return;
+    }
+
     run();
 }
 
@@ -20,6 +44,64 @@ fn run() {
     let _ = event_loop.run_app(&mut app);
 }
 
+fn run_headless(output_path: &str, extent: [f32; 2]) -> Result<(), Box<dyn std::error::Error>> {
+    Logger::log(LogLevel::Low, "main", "Starting headless render...");
+
+    let viewport_info = ViewportInfo::new([0.0, 0.0], extent);
+    let vulkan_container = VulkanContainer::new_headless(&viewport_info, RenderSettings::default());
+
+    let mut command_bus = App::create_command_bus(vulkan_container, None);
+    App::create_default_scene(&mut command_bus);
+    command_bus.update_managers();
+    command_bus.capture_frame(output_path)?;
+
+    Logger::log(LogLevel::Low, "main", "Headless render finished.");
+    Ok(())
+}
+
+//Renders a grid of cubes offscreen with and without instancing and reports how long recording the command buffer took.
+fn run_instancing_benchmark(cube_count: usize) {
+    const FRAMES: u32 = 60;
+
+    Logger::log(LogLevel::Low, "main", &format!("Starting instancing benchmark with {} cubes...", cube_count));
+
+    let viewport_info = ViewportInfo::new([0.0, 0.0], [800.0, 600.0]);
+    let mut vulkan_container = VulkanContainer::new_headless(&viewport_info, RenderSettings::default());
+
+    let mesh = Mesh::cube();
+    let grid_size = (cube_count as f32).sqrt().ceil() as usize;
+    for index in 0..cube_count {
+        let position = vec3(((index % grid_size) as f32 - grid_size as f32 / 2.0) * 1.5, 0.0, -((index / grid_size) as f32) * 1.5);
+        let transform = Transform::from_euler(position, Vec3::ZERO, Vec3::ONE);
+        vulkan_container.create_vulkan_object(index, &mesh, transform, MaterialHandle::default());
+    }
+
+    //Camera transforms are stored inverted, this places the camera above the front of the grid looking down into it.
+    let camera = Transform::from_euler(vec3(0.0, -10.0, -10.0), vec3(0.6, 0.0, 0.0), Vec3::ONE);
+    for instancing in [false, true] {
+        vulkan_container.set_instancing(instancing);
+
+        let mut total_recording_time = Duration::ZERO;
+        let mut draw_calls = 0;
+        for _ in 0..FRAMES {
+            vulkan_container.draw_frame(&camera);
+            total_recording_time += vulkan_container.get_frame_stats().recording_time;
+            draw_calls = vulkan_container.get_frame_stats().draw_calls;
+        }
+
+        Logger::log(LogLevel::Low, "main", &format!(
+            "Instancing {}: {} objects ({} culled) in {} draw calls with {} pipeline binds and {} material binds, {:.3} ms average command buffer recording time.",
+            if instancing { "on" } else { "off" },
+            vulkan_container.get_frame_stats().objects,
+            vulkan_container.get_frame_stats().culled_objects,
+            draw_calls,
+            vulkan_container.get_frame_stats().pipeline_binds,
+            vulkan_container.get_frame_stats().material_binds,
+            total_recording_time.as_secs_f64() * 1000.0 / FRAMES as f64,
+        ));
+    }
+}
+
 fn make_event_loop() -> EventLoop<()> {
     Logger::log(LogLevel::High, "window_wrapper", "Creating eventloop...");
 
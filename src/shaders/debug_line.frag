@@ -0,0 +1,11 @@
+#version 450
+
+// Input
+layout(location = 0) in vec3 fragColor;
+
+// Output
+layout(location = 0) out vec4 outColor;
+
+void main() {
+    outColor = vec4(fragColor, 1.0);
+}
\ No newline at end of file
@@ -0,0 +1,18 @@
+#version 450
+
+//Push constants
+layout(push_constant) uniform PushConstants {
+    mat4 view_projection;
+} pc;
+
+// Input
+layout(location = 0) in vec3 position;
+layout(location = 1) in vec3 color;
+
+// Output
+layout(location = 0) out vec3 fragColor;
+
+void main() {
+    gl_Position = pc.view_projection * vec4(position, 1.0);
+    fragColor = color;
+}
\ No newline at end of file
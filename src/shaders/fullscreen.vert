@@ -0,0 +1,11 @@
+#version 450
+
+// Output
+layout(location = 0) out vec2 fragTexCoord;
+
+void main() {
+    // One triangle big enough to cover the whole screen, no vertex buffer needed.
+    vec2 screen_position = vec2((gl_VertexIndex << 1) & 2, gl_VertexIndex & 2) * 2.0 - 1.0;
+    gl_Position = vec4(screen_position, 0.0, 1.0);
+    fragTexCoord = screen_position * 0.5 + 0.5;
+}
\ No newline at end of file
@@ -0,0 +1,23 @@
+#version 450
+
+//Set 2 holds the inputs of the post processing pass, the image before it and its parameters.
+layout(set = 2, binding = 0) uniform sampler2D source;
+layout(set = 2, binding = 2) uniform PostProcessUniforms {
+    vec4 parameters;
+    //xy is the size of one texel, zw the size of the image in texels.
+    vec4 texel_size;
+} post;
+
+// Input
+layout(location = 0) in vec2 fragTexCoord;
+
+// Output
+layout(location = 0) out vec4 outColor;
+
+// The top of the upsampled bloom chain, at half resolution.
+layout(set = 2, binding = 1) uniform sampler2D bloom;
+
+void main() {
+    vec4 color = texture(source, fragTexCoord);
+    outColor = vec4(color.rgb + texture(bloom, fragTexCoord).rgb * post.parameters.y, color.a);
+}
\ No newline at end of file
@@ -0,0 +1,29 @@
+#version 450
+
+//Set 2 holds the inputs of the post processing pass, the image before it and its parameters.
+layout(set = 2, binding = 0) uniform sampler2D source;
+layout(set = 2, binding = 2) uniform PostProcessUniforms {
+    vec4 parameters;
+    //xy is the size of one texel, zw the size of the image in texels.
+    vec4 texel_size;
+} post;
+
+// Input
+layout(location = 0) in vec2 fragTexCoord;
+
+// Output
+layout(location = 0) out vec4 outColor;
+
+vec3 sample_offset(float x, float y) {
+    return texture(source, fragTexCoord + vec2(x, y) * post.texel_size.xy).rgb;
+}
+
+// The 13 tap downsample from Call of Duty: Advanced Warfare, five overlapping boxes weighted so bright single pixels don't flicker.
+void main() {
+    vec3 center = sample_offset(0.0, 0.0);
+    vec3 corners = sample_offset(-2.0, -2.0) + sample_offset(2.0, -2.0) + sample_offset(-2.0, 2.0) + sample_offset(2.0, 2.0);
+    vec3 edges = sample_offset(0.0, -2.0) + sample_offset(-2.0, 0.0) + sample_offset(2.0, 0.0) + sample_offset(0.0, 2.0);
+    vec3 inner = sample_offset(-1.0, -1.0) + sample_offset(1.0, -1.0) + sample_offset(-1.0, 1.0) + sample_offset(1.0, 1.0);
+
+    outColor = vec4(center * 0.125 + corners * 0.03125 + edges * 0.0625 + inner * 0.125, 1.0);
+}
\ No newline at end of file
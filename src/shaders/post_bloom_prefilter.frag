@@ -0,0 +1,36 @@
+#version 450
+
+//Set 2 holds the inputs of the post processing pass, the image before it and its parameters.
+layout(set = 2, binding = 0) uniform sampler2D source;
+layout(set = 2, binding = 2) uniform PostProcessUniforms {
+    vec4 parameters;
+    //xy is the size of one texel, zw the size of the image in texels.
+    vec4 texel_size;
+} post;
+
+// Input
+layout(location = 0) in vec2 fragTexCoord;
+
+// Output
+layout(location = 0) out vec4 outColor;
+
+// Averages the four texels this half resolution pixel covers and keeps only what is brighter than the threshold.
+// The soft knee fades pixels in just below the threshold instead of cutting them off.
+void main() {
+    vec2 texel = post.texel_size.xy;
+    vec3 color = 0.25 * (
+        texture(source, fragTexCoord + vec2(-0.5, -0.5) * texel).rgb +
+        texture(source, fragTexCoord + vec2(0.5, -0.5) * texel).rgb +
+        texture(source, fragTexCoord + vec2(-0.5, 0.5) * texel).rgb +
+        texture(source, fragTexCoord + vec2(0.5, 0.5) * texel).rgb
+    );
+
+    float threshold = post.parameters.x;
+    float knee = threshold * 0.5;
+    float brightness = max(color.r, max(color.g, color.b));
+    float soft = clamp(brightness - threshold + knee, 0.0, 2.0 * knee);
+    soft = soft * soft / (4.0 * knee + 0.0001);
+    float contribution = max(soft, brightness - threshold) / max(brightness, 0.0001);
+
+    outColor = vec4(color * contribution, 1.0);
+}
\ No newline at end of file
@@ -0,0 +1,31 @@
+#version 450
+
+//Set 2 holds the inputs of the post processing pass, the image before it and its parameters.
+layout(set = 2, binding = 0) uniform sampler2D source;
+layout(set = 2, binding = 2) uniform PostProcessUniforms {
+    vec4 parameters;
+    //xy is the size of one texel, zw the size of the image in texels.
+    vec4 texel_size;
+} post;
+
+// Input
+layout(location = 0) in vec2 fragTexCoord;
+
+// Output
+layout(location = 0) out vec4 outColor;
+
+// The level of the downsample chain this pass writes the size of.
+layout(set = 2, binding = 1) uniform sampler2D level;
+
+vec3 sample_offset(float x, float y) {
+    return texture(source, fragTexCoord + vec2(x, y) * post.texel_size.xy).rgb;
+}
+
+// Blurs the smaller level with a 3x3 tent filter and adds it onto the downsampled level of this size.
+void main() {
+    vec3 blurred = sample_offset(0.0, 0.0) * 4.0;
+    blurred += (sample_offset(0.0, -1.0) + sample_offset(-1.0, 0.0) + sample_offset(1.0, 0.0) + sample_offset(0.0, 1.0)) * 2.0;
+    blurred += sample_offset(-1.0, -1.0) + sample_offset(1.0, -1.0) + sample_offset(-1.0, 1.0) + sample_offset(1.0, 1.0);
+
+    outColor = vec4(texture(level, fragTexCoord).rgb + blurred / 16.0, 1.0);
+}
\ No newline at end of file
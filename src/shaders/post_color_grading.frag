@@ -0,0 +1,41 @@
+#version 450
+
+//Set 2 holds the inputs of the post processing pass, the image before it and its parameters.
+layout(set = 2, binding = 0) uniform sampler2D source;
+layout(set = 2, binding = 2) uniform PostProcessUniforms {
+    vec4 parameters;
+    //xy is the size of one texel, zw the size of the image in texels.
+    vec4 texel_size;
+} post;
+
+// Input
+layout(location = 0) in vec2 fragTexCoord;
+
+// Output
+layout(location = 0) out vec4 outColor;
+
+//The lookup table is an sRGB image, so it is indexed with sRGB colors and sampling it gives back linear ones.
+layout(set = 2, binding = 1) uniform sampler2D lut;
+
+vec3 linear_to_srgb(vec3 color) {
+    bvec3 cutoff = lessThan(color, vec3(0.0031308));
+    return mix(1.055 * pow(color, vec3(1.0 / 2.4)) - 0.055, color * 12.92, cutoff);
+}
+
+vec2 slice_uv(vec2 red_green, float slice, float size) {
+    vec2 texel = (red_green * (size - 1.0) + 0.5) / vec2(size * size, size);
+    return texel + vec2(slice / size, 0.0);
+}
+
+void main() {
+    vec4 color = texture(source, fragTexCoord);
+    vec3 srgb = clamp(linear_to_srgb(max(color.rgb, vec3(0.0))), 0.0, 1.0);
+
+    float size = float(textureSize(lut, 0).y);
+    float blue = srgb.b * (size - 1.0);
+    float slice = floor(blue);
+    vec3 lower = texture(lut, slice_uv(srgb.rg, slice, size)).rgb;
+    vec3 upper = texture(lut, slice_uv(srgb.rg, min(slice + 1.0, size - 1.0), size)).rgb;
+
+    outColor = vec4(mix(lower, upper, blue - slice), color.a);
+}
\ No newline at end of file
@@ -0,0 +1,46 @@
+#version 450
+
+//Set 2 holds the inputs of the post processing pass, the image before it and its parameters.
+layout(set = 2, binding = 0) uniform sampler2D source;
+layout(set = 2, binding = 2) uniform PostProcessUniforms {
+    vec4 parameters;
+    //xy is the size of one texel, zw the size of the image in texels.
+    vec4 texel_size;
+} post;
+
+// Input
+layout(location = 0) in vec2 fragTexCoord;
+
+// Output
+layout(location = 0) out vec4 outColor;
+
+const float FXAA_SPAN_MAX = 8.0;
+const float FXAA_REDUCE_MUL = 1.0 / 8.0;
+const float FXAA_REDUCE_MIN = 1.0 / 128.0;
+const vec3 LUMA = vec3(0.299, 0.587, 0.114);
+
+// Blurs along edges found from the luma of the four diagonal neighbours, pixels the blur would overshoot keep a shorter blur.
+void main() {
+    vec2 texel = post.texel_size.xy;
+    vec4 center = texture(source, fragTexCoord);
+
+    float luma_nw = dot(texture(source, fragTexCoord + vec2(-1.0, -1.0) * texel).rgb, LUMA);
+    float luma_ne = dot(texture(source, fragTexCoord + vec2(1.0, -1.0) * texel).rgb, LUMA);
+    float luma_sw = dot(texture(source, fragTexCoord + vec2(-1.0, 1.0) * texel).rgb, LUMA);
+    float luma_se = dot(texture(source, fragTexCoord + vec2(1.0, 1.0) * texel).rgb, LUMA);
+    float luma_center = dot(center.rgb, LUMA);
+
+    float luma_min = min(luma_center, min(min(luma_nw, luma_ne), min(luma_sw, luma_se)));
+    float luma_max = max(luma_center, max(max(luma_nw, luma_ne), max(luma_sw, luma_se)));
+
+    vec2 direction = vec2(-((luma_nw + luma_ne) - (luma_sw + luma_se)), (luma_nw + luma_sw) - (luma_ne + luma_se));
+    float direction_reduce = max((luma_nw + luma_ne + luma_sw + luma_se) * 0.25 * FXAA_REDUCE_MUL, FXAA_REDUCE_MIN);
+    float inverse_direction_min = 1.0 / (min(abs(direction.x), abs(direction.y)) + direction_reduce);
+    direction = clamp(direction * inverse_direction_min, vec2(-FXAA_SPAN_MAX), vec2(FXAA_SPAN_MAX)) * texel;
+
+    vec3 color_a = 0.5 * (texture(source, fragTexCoord + direction * (1.0 / 3.0 - 0.5)).rgb + texture(source, fragTexCoord + direction * (2.0 / 3.0 - 0.5)).rgb);
+    vec3 color_b = color_a * 0.5 + 0.25 * (texture(source, fragTexCoord - direction * 0.5).rgb + texture(source, fragTexCoord + direction * 0.5).rgb);
+    float luma_b = dot(color_b, LUMA);
+
+    outColor = vec4((luma_b < luma_min || luma_b > luma_max) ? color_a : color_b, center.a);
+}
\ No newline at end of file
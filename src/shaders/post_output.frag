@@ -0,0 +1,20 @@
+#version 450
+
+//Set 2 holds the inputs of the post processing pass, the image before it and its parameters.
+layout(set = 2, binding = 0) uniform sampler2D source;
+layout(set = 2, binding = 2) uniform PostProcessUniforms {
+    vec4 parameters;
+    //xy is the size of one texel, zw the size of the image in texels.
+    vec4 texel_size;
+} post;
+
+// Input
+layout(location = 0) in vec2 fragTexCoord;
+
+// Output
+layout(location = 0) out vec4 outColor;
+
+// Whatever the chain left behind is written to the presented image, anything still outside the displayable range is clamped.
+void main() {
+    outColor = vec4(clamp(texture(source, fragTexCoord).rgb, 0.0, 1.0), 1.0);
+}
\ No newline at end of file
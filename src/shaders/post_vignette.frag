@@ -0,0 +1,22 @@
+#version 450
+
+//Set 2 holds the inputs of the post processing pass, the image before it and its parameters.
+layout(set = 2, binding = 0) uniform sampler2D source;
+layout(set = 2, binding = 2) uniform PostProcessUniforms {
+    vec4 parameters;
+    //xy is the size of one texel, zw the size of the image in texels.
+    vec4 texel_size;
+} post;
+
+// Input
+layout(location = 0) in vec2 fragTexCoord;
+
+// Output
+layout(location = 0) out vec4 outColor;
+
+void main() {
+    vec4 color = texture(source, fragTexCoord);
+    float distance_from_center = length(fragTexCoord * 2.0 - 1.0);
+    float vignette = 1.0 - post.parameters.x * smoothstep(post.parameters.y, post.parameters.y + 0.5, distance_from_center);
+    outColor = vec4(color.rgb * vignette, color.a);
+}
\ No newline at end of file
@@ -1,14 +1,105 @@
 #version 450
 
+// Has to match MAX_POINT_LIGHTS in uniform_buffer_object.rs
+#define MAX_POINT_LIGHTS 8
+// Has to match MAX_SHADOW_CASCADES in shadow.rs
+#define MAX_SHADOW_CASCADES 4
+
+struct PointLight {
+    vec4 position_range;
+    vec4 color_intensity;
+};
+
 // Input
 layout(location = 0) in vec3 fragColor;
 layout(location = 1) in vec2 texCoord;
+layout(location = 2) in vec3 world_position;
+layout(location = 3) in vec3 normal;
 
 // Output
 layout(location = 0) out vec4 outColor;
-layout(binding = 1) uniform sampler2D tex_sampler;
+
+// Per frame data
+layout(set = 0, binding = 0) uniform FrameUniforms {
+    vec4 ambient_color;
+    vec4 camera_position;
+    vec4 light_direction;
+    vec4 light_color_intensity;
+    PointLight point_lights[MAX_POINT_LIGHTS];
+    uvec4 point_light_count;
+    mat4 shadow_matrices[MAX_SHADOW_CASCADES];
+    // x is the bias, y the amount of cascades and zw the size of a shadow map texel.
+    vec4 shadow_parameters;
+} frame;
+layout(set = 0, binding = 1) uniform samplerCube environment_map;
+layout(set = 0, binding = 2) uniform sampler2DShadow shadow_map;
+
+// Per material data
+layout(set = 1, binding = 0) uniform sampler2D tex_sampler;
+layout(set = 1, binding = 1) uniform MaterialUniforms {
+    vec4 base_color;
+    vec4 tint;
+    // x is how much of the environment map gets reflected.
+    vec4 surface;
+    // rgb is given off as light, w scales the vertex color given off on top of it.
+    vec4 emission;
+} material;
+
+// How much of the directional light reaches the position, looked up in the first cascade covering it.
+// Cascades sit side by side in the shadow map, nine filtered taps soften the shadow edges.
+float directional_shadow(vec3 position, vec3 surface_normal) {
+    uint cascades = uint(frame.shadow_parameters.y);
+    vec2 texel = frame.shadow_parameters.zw;
+    // Keeps every tap inside the square of its cascade.
+    float margin = 2.0 * texel.y;
+    vec3 biased_position = position + surface_normal * frame.shadow_parameters.x;
+
+    for (uint i = 0; i < cascades; i++) {
+        vec4 shadow_position = frame.shadow_matrices[i] * vec4(biased_position, 1.0);
+        vec3 shadow_coords = shadow_position.xyz / shadow_position.w;
+        vec2 cascade_uv = shadow_coords.xy * 0.5 + 0.5;
+        if (any(lessThan(cascade_uv, vec2(margin))) || any(greaterThan(cascade_uv, vec2(1.0 - margin))) || shadow_coords.z < 0.0 || shadow_coords.z > 1.0) {
+            continue;
+        }
+
+        vec2 shadow_uv = vec2((cascade_uv.x + float(i)) / float(cascades), cascade_uv.y);
+        float lit = 0.0;
+        for (int x = -1; x <= 1; x++) {
+            for (int y = -1; y <= 1; y++) {
+                lit += texture(shadow_map, vec3(shadow_uv + vec2(x, y) * texel, shadow_coords.z));
+            }
+        }
+        return lit / 9.0;
+    }
+
+    return 1.0;
+}
 
 void main() {
-    vec4 tex_color = texture(tex_sampler, texCoord);
-    outColor = tex_color;
+    vec3 surface_normal = normalize(normal);
+    vec3 lighting = frame.ambient_color.rgb;
+
+    float directional_diffuse = max(dot(surface_normal, -frame.light_direction.xyz), 0.0);
+    if (directional_diffuse > 0.0) {
+        directional_diffuse *= directional_shadow(world_position, surface_normal);
+    }
+    lighting += directional_diffuse * frame.light_color_intensity.rgb * frame.light_color_intensity.w;
+
+    for (uint i = 0; i < frame.point_light_count.x; i++) {
+        PointLight point_light = frame.point_lights[i];
+        vec3 to_light = point_light.position_range.xyz - world_position;
+        float distance = length(to_light);
+        float falloff = clamp(1.0 - distance / point_light.position_range.w, 0.0, 1.0);
+
+        float point_diffuse = max(dot(surface_normal, to_light / max(distance, 0.0001)), 0.0);
+        lighting += point_diffuse * falloff * falloff * point_light.color_intensity.rgb * point_light.color_intensity.w;
+    }
+
+    vec4 surface_color = texture(tex_sampler, texCoord) * material.base_color * material.tint;
+    vec3 view_direction = normalize(world_position - frame.camera_position.xyz);
+    vec3 reflection = texture(environment_map, reflect(view_direction, surface_normal)).rgb;
+    vec3 color = mix(surface_color.rgb * lighting, reflection, material.surface.x);
+    color += material.emission.rgb + fragColor * material.emission.w;
+
+    outColor = vec4(color, surface_color.a);
 }
\ No newline at end of file
@@ -2,20 +2,32 @@
 
 //Push constants
 layout(push_constant) uniform PushConstants {
-    mat4 mvp;
+    mat4 view_projection;
 } pc;
 
 // Input
 layout(location = 0) in vec3 position;
 layout(location = 1) in vec3 color;
 layout(location = 2) in vec2 texCoord;
+layout(location = 3) in vec3 normal;
+
+// Instance input
+layout(location = 4) in mat4 model;
 
 // Output
 layout(location = 0) out vec3 fragColor;
 layout(location = 1) out vec2 frag_tex_coord;
+layout(location = 2) out vec3 frag_world_position;
+layout(location = 3) out vec3 frag_normal;
 
 void main() {
-    gl_Position = pc.mvp * vec4(position, 1.0);
+    vec4 world_position = model * vec4(position, 1.0);
+    // Inverse transpose keeps normals perpendicular to their faces under non uniform scale.
+    mat3 normal_matrix = transpose(inverse(mat3(model)));
+
+    gl_Position = pc.view_projection * world_position;
     fragColor = color;
     frag_tex_coord = texCoord;
+    frag_world_position = world_position.xyz;
+    frag_normal = normal_matrix * normal;
 }
\ No newline at end of file
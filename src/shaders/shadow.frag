@@ -0,0 +1,5 @@
+#version 450
+
+// Only depth is written, there is no color attachment to output to.
+void main() {
+}
\ No newline at end of file
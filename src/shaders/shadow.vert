@@ -0,0 +1,16 @@
+#version 450
+
+//Push constants, the view projection of the cascade being drawn
+layout(push_constant) uniform PushConstants {
+    mat4 view_projection;
+} pc;
+
+// Input
+layout(location = 0) in vec3 position;
+
+// Instance input
+layout(location = 4) in mat4 model;
+
+void main() {
+    gl_Position = pc.view_projection * model * vec4(position, 1.0);
+}
\ No newline at end of file
@@ -0,0 +1,16 @@
+#version 450
+
+// Input
+layout(location = 0) in vec4 near_point;
+layout(location = 1) in vec4 far_point;
+
+// Output
+layout(location = 0) out vec4 outColor;
+
+// Per frame data
+layout(set = 0, binding = 1) uniform samplerCube environment_map;
+
+void main() {
+    vec3 direction = far_point.xyz / far_point.w - near_point.xyz / near_point.w;
+    outColor = vec4(texture(environment_map, direction).rgb, 1.0);
+}
\ No newline at end of file
@@ -0,0 +1,21 @@
+#version 450
+
+//Push constants
+layout(push_constant) uniform PushConstants {
+    mat4 view_projection;
+} pc;
+
+// Output
+layout(location = 0) out vec4 near_point;
+layout(location = 1) out vec4 far_point;
+
+void main() {
+    // One triangle big enough to cover the whole screen, no vertex buffer needed.
+    vec2 screen_position = vec2((gl_VertexIndex << 1) & 2, gl_VertexIndex & 2) * 2.0 - 1.0;
+    gl_Position = vec4(screen_position, 1.0, 1.0);
+
+    // Two points along the view ray through this corner, the fragment shader subtracts them so the camera position drops out.
+    mat4 inverse_view_projection = inverse(pc.view_projection);
+    near_point = inverse_view_projection * vec4(screen_position, 0.0, 1.0);
+    far_point = inverse_view_projection * vec4(screen_position, 1.0, 1.0);
+}
\ No newline at end of file
@@ -0,0 +1,15 @@
+#version 450
+
+//The glyph atlas is bound in place of a material texture, its alpha is the glyph coverage.
+layout(set = 1, binding = 0) uniform sampler2D glyphAtlas;
+
+// Input
+layout(location = 0) in vec2 fragTexCoord;
+layout(location = 1) in vec4 fragColor;
+
+// Output
+layout(location = 0) out vec4 outColor;
+
+void main() {
+    outColor = vec4(fragColor.rgb, fragColor.a * texture(glyphAtlas, fragTexCoord).a);
+}
\ No newline at end of file
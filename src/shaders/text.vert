@@ -0,0 +1,16 @@
+#version 450
+
+// Input
+layout(location = 0) in vec2 position;
+layout(location = 1) in vec2 tex_coord;
+layout(location = 2) in vec4 color;
+
+// Output
+layout(location = 0) out vec2 fragTexCoord;
+layout(location = 1) out vec4 fragColor;
+
+void main() {
+    gl_Position = vec4(position, 0.0, 1.0);
+    fragTexCoord = tex_coord;
+    fragColor = color;
+}
\ No newline at end of file
@@ -0,0 +1,16 @@
+#version 450
+
+//The UI texture is bound in place of a material texture.
+layout(set = 1, binding = 0) uniform sampler2D uiTexture;
+
+// Input
+layout(location = 0) in vec2 fragTexCoord;
+layout(location = 1) in vec4 fragColor;
+
+// Output
+layout(location = 0) out vec4 outColor;
+
+//Colors are premultiplied by alpha, the ui pipeline blends accordingly.
+void main() {
+    outColor = fragColor * texture(uiTexture, fragTexCoord);
+}
\ No newline at end of file
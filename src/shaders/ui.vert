@@ -0,0 +1,27 @@
+#version 450
+
+//Push constants, for the UI this maps points to clip space.
+layout(push_constant) uniform PushConstants {
+    mat4 view_projection;
+} pc;
+
+// Input
+layout(location = 0) in vec2 position;
+layout(location = 1) in vec2 tex_coord;
+layout(location = 2) in vec4 color;
+
+// Output
+layout(location = 0) out vec2 fragTexCoord;
+layout(location = 1) out vec4 fragColor;
+
+//egui hands out sRGB vertex colors, but blending into the sRGB framebuffer happens in linear space.
+vec3 srgb_to_linear(vec3 srgb) {
+    bvec3 cutoff = lessThan(srgb, vec3(0.04045));
+    return mix(pow((srgb + 0.055) / 1.055, vec3(2.4)), srgb / 12.92, cutoff);
+}
+
+void main() {
+    gl_Position = pc.view_projection * vec4(position, 0.0, 1.0);
+    fragTexCoord = tex_coord;
+    fragColor = vec4(srgb_to_linear(color.rgb), color.a);
+}
\ No newline at end of file
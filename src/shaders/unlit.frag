@@ -0,0 +1,25 @@
+#version 450
+
+// Input
+layout(location = 0) in vec3 fragColor;
+layout(location = 1) in vec2 texCoord;
+layout(location = 2) in vec3 world_position;
+layout(location = 3) in vec3 normal;
+
+// Output
+layout(location = 0) out vec4 outColor;
+
+// Per material data
+layout(set = 1, binding = 0) uniform sampler2D tex_sampler;
+layout(set = 1, binding = 1) uniform MaterialUniforms {
+    vec4 base_color;
+    vec4 tint;
+    vec4 surface;
+    // rgb is given off as light, w scales the vertex color given off on top of it.
+    vec4 emission;
+} material;
+
+void main() {
+    vec4 color = texture(tex_sampler, texCoord) * material.base_color * material.tint;
+    outColor = vec4(color.rgb + material.emission.rgb + fragColor * material.emission.w, color.a);
+}
\ No newline at end of file